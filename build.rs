@@ -1,8 +1,27 @@
-use std::env;
+use std::{env, fs, path::Path};
 
 use glib_build_tools::compile_resources;
 
+/// pack the embedded π digits two per byte (BCD, high nibble first) to halve the size of the binary
+fn pack_pi_digits(out_dir: &Path) {
+    println!("cargo:rerun-if-changed=data/app/1m");
+    let digits = fs::read("data/app/1m").expect("the π digits should be readable");
+    assert!(
+        digits.iter().all(|digit| *digit <= 9),
+        "the π digits should be stored one per byte"
+    );
+
+    let packed: Vec<u8> = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect();
+    fs::write(out_dir.join("pi_digits.bcd"), packed).expect("the packed digits should be writable");
+}
+
 fn main() {
+    let out_dir = env::var("OUT_DIR").expect("cargo should set OUT_DIR");
+    pack_pi_digits(Path::new(&out_dir));
+
     env::set_var("OUT_DIR", "./");
     compile_resources(&["data"], "data/icons.gresource.xml", "icons.gresource");
 }
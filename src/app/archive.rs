@@ -0,0 +1,140 @@
+use super::storage::StorageError;
+
+const BLOCK_LEN: usize = 512;
+const NAME_LEN: usize = 100;
+const SIZE_RANGE: std::ops::Range<usize> = 124..136;
+const CHECKSUM_RANGE: std::ops::Range<usize> = 148..156;
+const TYPE_FLAG: usize = 156;
+const MAGIC_RANGE: std::ops::Range<usize> = 257..265;
+
+/// A file of an archive, its name is a relative path with "/" separators
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+/// sum of the header bytes, the checksum field counting as spaces
+fn checksum(header: &[u8]) -> u32 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| match CHECKSUM_RANGE.contains(&i) {
+            true => b' ' as u32,
+            false => *byte as u32,
+        })
+        .sum()
+}
+
+/// write `value` in octal on the whole field, ending with a NUL
+fn write_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{value:0width$o}");
+    field[..width].copy_from_slice(digits.as_bytes());
+}
+
+fn read_octal(field: &[u8]) -> Result<u64, StorageError> {
+    let text = std::str::from_utf8(field).map_err(|_| StorageError::Corrupted)?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    u64::from_str_radix(text, 8).map_err(|_| StorageError::Corrupted)
+}
+
+/// a ustar tarball of regular files, readable by the usual archive tools
+pub fn write_tar(entries: &[Entry]) -> Vec<u8> {
+    let mut tar = vec![];
+    for entry in entries {
+        let mut header = [0u8; BLOCK_LEN];
+        let name = entry.name.as_bytes();
+        // the names of the app's files are far shorter
+        let name_len = name.len().min(NAME_LEN - 1);
+        header[..name_len].copy_from_slice(&name[..name_len]);
+        write_octal(&mut header[100..108], 0o644); // mode
+        write_octal(&mut header[108..116], 0); // uid
+        write_octal(&mut header[116..124], 0); // gid
+        write_octal(&mut header[SIZE_RANGE], entry.content.len() as u64);
+        write_octal(&mut header[136..148], 0); // mtime
+        header[TYPE_FLAG] = b'0';
+        header[MAGIC_RANGE].copy_from_slice(b"ustar\x0000");
+
+        let checksum = format!("{:06o}\0 ", checksum(&header));
+        header[CHECKSUM_RANGE].copy_from_slice(checksum.as_bytes());
+
+        tar.extend_from_slice(&header);
+        tar.extend_from_slice(&entry.content);
+        tar.resize(tar.len().next_multiple_of(BLOCK_LEN), 0);
+    }
+    // the end of the archive is marked by two empty blocks
+    tar.resize(tar.len() + 2 * BLOCK_LEN, 0);
+    tar
+}
+
+/// the regular files of a tarball, the directories and links are skipped
+pub fn read_tar(tar: &[u8]) -> Result<Vec<Entry>, StorageError> {
+    let mut entries = vec![];
+    let mut offset = 0;
+    while let Some(header) = tar.get(offset..offset + BLOCK_LEN) {
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(entries);
+        }
+        if read_octal(&header[CHECKSUM_RANGE])? != checksum(header) as u64 {
+            return Err(StorageError::Corrupted);
+        }
+
+        let size = read_octal(&header[SIZE_RANGE])? as usize;
+        let content_start = offset + BLOCK_LEN;
+        let content = tar
+            .get(content_start..content_start + size)
+            .ok_or(StorageError::Corrupted)?;
+        if let b'0' | b'\0' = header[TYPE_FLAG] {
+            let name_end = header[..NAME_LEN]
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(NAME_LEN);
+            let name =
+                std::str::from_utf8(&header[..name_end]).map_err(|_| StorageError::Corrupted)?;
+            entries.push(Entry {
+                name: name.to_string(),
+                content: content.to_vec(),
+            });
+        }
+        offset = content_start + size.next_multiple_of(BLOCK_LEN);
+    }
+    Err(StorageError::Corrupted) // truncated before the end blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tar_round_trips() {
+        let entries = vec![
+            Entry {
+                name: "preferences.conf".to_string(),
+                content: b"version = 1\n".to_vec(),
+            },
+            Entry {
+                name: "stats/0/10/history".to_string(),
+                content: vec![b'1'; BLOCK_LEN],
+            },
+            Entry {
+                name: "empty".to_string(),
+                content: vec![],
+            },
+        ];
+        let tar = write_tar(&entries);
+        assert_eq!(tar.len() % BLOCK_LEN, 0);
+        assert_eq!(read_tar(&tar).unwrap(), entries);
+    }
+
+    #[test]
+    fn damaged_tar_is_rejected() {
+        let mut tar = write_tar(&[Entry {
+            name: "history".to_string(),
+            content: b"1 2 3".to_vec(),
+        }]);
+        assert!(read_tar(&tar[..BLOCK_LEN + 2]).is_err());
+        tar[0] = b'X';
+        assert!(read_tar(&tar).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::storage::{get_stats_file_path, StorageError};
+use crate::{
+    digits::Sequence,
+    game::{AppMode, GameSummary},
+};
+
+pub const HISTORY_FILE_NAME: &str = "history";
+/// the modes where the digits are typed from memory from the start offset, the only ones charted as the best
+const RECALL_MODES: [AppMode; 2] = [AppMode::Blind, AppMode::InstantDeath];
+
+/// the timeouts are compared at the millisecond precision of the history file
+fn timeout_ms(timeout: Option<Duration>) -> u128 {
+    timeout.unwrap_or_default().as_millis()
+}
+
+/// A finished game as stored in the history file
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SessionRecord {
+    pub mode: AppMode,
+    pub timeout: Option<Duration>,
+    pub start_offset: usize,
+    pub digits_reached: usize,
+    pub errors: usize,
+    pub duration: Duration,
+    /// in seconds since the unix epoch
+    pub timestamp: u64,
+    /// quiz points, 0 in the other modes
+    pub score: usize,
+    /// mean time to pick the right chunk in choice mode, zero in the other modes
+    pub reaction_time: Duration,
+}
+
+impl SessionRecord {
+    pub fn new(summary: &GameSummary) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Self {
+            mode: summary.mode,
+            timeout: summary.timeout,
+            start_offset: summary.start_index,
+            digits_reached: summary.digits_reached,
+            errors: summary.errors,
+            duration: summary.elapsed,
+            timestamp,
+            score: summary.score,
+            reaction_time: summary.reaction_time,
+        }
+    }
+
+    pub fn digits_per_minute(&self) -> f64 {
+        let minutes = self.duration.as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0.0;
+        }
+        self.digits_reached as f64 / minutes
+    }
+
+    /// what a personal best is about: the score in quiz mode, the digits reached otherwise
+    pub fn result(&self) -> usize {
+        match self.mode {
+            AppMode::Quiz => self.score,
+            _ => self.digits_reached,
+        }
+    }
+
+    /// number of digits the user typed, wrong ones included
+    pub fn typed_digits(&self) -> usize {
+        // the digits reached are right ones, they stop at the first mistake in the recall modes
+        self.digits_reached + self.errors
+    }
+
+    /// one line per record: "timestamp mode timeout_ms start_offset digits_reached errors duration_ms score reaction_ms"
+    fn to_line(self) -> String {
+        format!(
+            "{} {} {} {} {} {} {} {} {}",
+            self.timestamp,
+            self.mode as u8,
+            timeout_ms(self.timeout),
+            self.start_offset,
+            self.digits_reached,
+            self.errors,
+            self.duration.as_millis(),
+            self.score,
+            self.reaction_time.as_millis()
+        )
+    }
+
+    fn from_line(line: &str) -> Result<Self, ()> {
+        let fields = line
+            .split_whitespace()
+            .map(|field| field.parse::<u64>().map_err(|_| ()))
+            .collect::<Result<Vec<_>, _>>()?;
+        // the score was added with the quiz mode and the reaction time with the choice mode
+        if !(7..=9).contains(&fields.len()) {
+            return Err(()); // data corrupted
+        }
+
+        let mode = AppMode::try_from(u8::try_from(fields[1]).map_err(|_| ())?)?;
+        let timeout = match fields[2] {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        };
+
+        Ok(Self {
+            mode,
+            timeout,
+            start_offset: fields[3] as usize,
+            digits_reached: fields[4] as usize,
+            errors: fields[5] as usize,
+            duration: Duration::from_millis(fields[6]),
+            timestamp: fields[0],
+            score: fields.get(7).cloned().unwrap_or_default() as usize,
+            reaction_time: Duration::from_millis(fields.get(8).cloned().unwrap_or_default()),
+        })
+    }
+}
+
+/// add the records of `content` missing from `records`, keeping them sorted by date
+fn merge_records(records: &mut Vec<SessionRecord>, content: &str) -> usize {
+    let len = records.len();
+    for record in content
+        .lines()
+        .filter_map(|line| SessionRecord::from_line(line).ok())
+    {
+        if !records.contains(&record) {
+            records.push(record);
+        }
+    }
+    records.sort_by_key(|record| record.timestamp);
+    records.len() - len
+}
+
+/// Aggregation of all the games played in one day
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DayStats {
+    /// in days since the unix epoch
+    pub day: u64,
+    pub sessions: usize,
+    /// best digits reached up to this day (included), in the recall modes
+    pub best_so_far: usize,
+    pub avg_digits_per_minute: f64,
+    /// wrong digits over typed digits, between 0.0 and 1.0
+    pub error_rate: f64,
+}
+
+/// Every game the user finished, oldest first
+#[derive(Debug)]
+pub struct History {
+    sequence: Sequence,
+    base: u8,
+    records: Vec<SessionRecord>,
+}
+
+impl History {
+    pub fn load(sequence: Sequence, base: u8) -> Self {
+        let history_file_path = match get_stats_file_path(sequence, base, HISTORY_FILE_NAME) {
+            Ok(path) => path,
+            Err(_) => {
+                return Self {
+                    sequence,
+                    base,
+                    records: vec![],
+                }
+            }
+        };
+        let content = fs::read_to_string(history_file_path).unwrap_or_default();
+
+        // a corrupted line should not cost the whole history
+        let records = content
+            .lines()
+            .filter_map(|line| SessionRecord::from_line(line).ok())
+            .collect();
+        Self {
+            sequence,
+            base,
+            records,
+        }
+    }
+
+    /// best result for this mode and timeout combination
+    pub fn personal_best(&self, mode: AppMode, timeout: Option<Duration>) -> Option<usize> {
+        self.records
+            .iter()
+            .filter(|record| {
+                record.mode == mode && timeout_ms(record.timeout) == timeout_ms(timeout)
+            })
+            .map(|record| record.result())
+            .max()
+    }
+
+    /// one entry per day with at least one game, oldest first
+    pub fn daily_stats(&self) -> Vec<DayStats> {
+        let mut records = self.records.clone();
+        records.sort_by_key(|record| record.timestamp);
+
+        let mut days: Vec<DayStats> = vec![];
+        let mut best_so_far = 0;
+        for day_records in records.chunk_by(|a, b| a.timestamp / 86400 == b.timestamp / 86400) {
+            best_so_far = day_records
+                .iter()
+                .filter(|record| RECALL_MODES.contains(&record.mode))
+                .map(|record| record.digits_reached)
+                .fold(best_so_far, usize::max);
+
+            let sessions = day_records.len();
+            let avg_digits_per_minute = day_records
+                .iter()
+                .map(|record| record.digits_per_minute())
+                .sum::<f64>()
+                / sessions as f64;
+
+            let typed = day_records.iter().map(|r| r.typed_digits()).sum::<usize>();
+            let errors = day_records.iter().map(|r| r.errors).sum::<usize>();
+            let error_rate = match typed {
+                0 => 0.0,
+                typed => errors as f64 / typed as f64,
+            };
+
+            days.push(DayStats {
+                day: day_records[0].timestamp / 86400,
+                sessions,
+                best_so_far,
+                avg_digits_per_minute,
+                error_rate,
+            });
+        }
+        days
+    }
+
+    /// add the games of another history file that are not there yet, return how many were added
+    pub fn merge(&mut self, content: &str) -> Result<usize, StorageError> {
+        let added = merge_records(&mut self.records, content);
+
+        let history_file_path = get_stats_file_path(self.sequence, self.base, HISTORY_FILE_NAME)?;
+        let content = self
+            .records
+            .iter()
+            .map(|record| record.to_line() + "\n")
+            .collect::<String>();
+        fs::write(history_file_path, content)?;
+        Ok(added)
+    }
+
+    /// save the record in memory and append it to the history file
+    pub fn push(&mut self, record: SessionRecord) -> Result<(), StorageError> {
+        self.records.push(record);
+
+        let history_file_path = get_stats_file_path(self.sequence, self.base, HISTORY_FILE_NAME)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(history_file_path)?;
+        writeln!(file, "{}", record.to_line())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::preferences::AppPreferences,
+        digits::Digits,
+        game::{GameEvent, GameInput, GameSession},
+    };
+
+    fn record(
+        mode: AppMode,
+        digits_reached: usize,
+        errors: usize,
+        timestamp: u64,
+    ) -> SessionRecord {
+        SessionRecord {
+            mode,
+            timeout: None,
+            start_offset: 0,
+            digits_reached,
+            errors,
+            duration: Duration::from_secs(60),
+            timestamp,
+            score: 0,
+            reaction_time: Duration::ZERO,
+        }
+    }
+
+    fn history(records: Vec<SessionRecord>) -> History {
+        History {
+            sequence: Sequence::Pi,
+            base: 10,
+            records,
+        }
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let mut record = record(AppMode::Choice, 42, 3, 1_700_000_000);
+        record.timeout = Some(Duration::from_millis(2500));
+        record.reaction_time = Duration::from_millis(1200);
+        assert_eq!(SessionRecord::from_line(&record.to_line()), Ok(record));
+
+        // written before the quiz and choice modes
+        let old = SessionRecord::from_line("1700000000 0 0 10 42 3 60000").unwrap();
+        assert_eq!(old.score, 0);
+        assert_eq!(old.reaction_time, Duration::ZERO);
+        assert!(SessionRecord::from_line("1700000000 0 0 10").is_err());
+        assert!(SessionRecord::from_line("1700000000 99 0 10 42 3 60000").is_err());
+    }
+
+    #[test]
+    fn personal_best_is_per_mode_and_timeout() {
+        let mut timed = record(AppMode::Blind, 80, 0, 0);
+        timed.timeout = Some(Duration::from_secs(5));
+        let mut quiz = record(AppMode::Quiz, 30, 0, 0);
+        quiz.score = 7;
+        let history = history(vec![
+            record(AppMode::Blind, 20, 0, 0),
+            record(AppMode::Blind, 50, 2, 0),
+            timed,
+            record(AppMode::Learn, 500, 0, 0),
+            quiz,
+        ]);
+
+        assert_eq!(history.personal_best(AppMode::Blind, None), Some(50));
+        assert_eq!(
+            history.personal_best(AppMode::Blind, Some(Duration::from_secs(5))),
+            Some(80)
+        );
+        assert_eq!(history.personal_best(AppMode::Quiz, None), Some(7));
+        assert_eq!(history.personal_best(AppMode::Drill, None), None);
+    }
+
+    #[test]
+    fn daily_stats_group_the_games_by_day() {
+        let day = 19_000 * 86400;
+        let history = history(vec![
+            record(AppMode::Blind, 30, 10, day + 3600),
+            // out of order in the file
+            record(AppMode::Blind, 10, 0, day + 2 * 86400),
+            record(AppMode::InstantDeath, 50, 0, day + 7200),
+            // the digits are shown in learn mode
+            record(AppMode::Learn, 900, 0, day + 2 * 86400 + 60),
+        ]);
+        let days = history.daily_stats();
+
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].day, days[0].sessions), (19_000, 2));
+        assert_eq!((days[1].day, days[1].sessions), (19_002, 2));
+        assert_eq!(days[0].best_so_far, 50);
+        assert_eq!(days[1].best_so_far, 50);
+        assert_eq!(days[0].avg_digits_per_minute, 40.0);
+        // 10 errors over 30 + 10 + 50 + 0 typed digits
+        assert_eq!(days[0].error_rate, 10.0 / 90.0);
+        assert_eq!(days[1].error_rate, 0.0);
+    }
+
+    #[test]
+    fn key_mashing_does_not_raise_the_personal_best() {
+        let mut history = history(vec![record(AppMode::Blind, 20, 0, 0)]);
+        let preferences = AppPreferences {
+            mode: AppMode::Blind,
+            ..Default::default()
+        };
+        let mut game = GameSession::new(preferences.game_settings(Digits::embedded(), 0));
+        game.start();
+        for _ in 0..500 {
+            game.handle(GameInput::Digit(0));
+        }
+        let summary = match game.handle(GameInput::Finish).as_slice() {
+            [GameEvent::GameOver(summary)] => *summary,
+            events => panic!("finish did not end the game: {events:?}"),
+        };
+        assert!(summary.errors > 400);
+        assert!(summary.digits_reached < 20);
+
+        history.records.push(SessionRecord::new(&summary));
+        assert_eq!(history.personal_best(AppMode::Blind, None), Some(20));
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, OpenOptions},
+    io::Write,
+};
+
+use super::storage::{get_stats_file_path, StorageError};
+use crate::{digits::Sequence, game::Mistake};
+
+pub const MISTAKES_FILE_NAME: &str = "mistakes";
+const COMMON_CONFUSIONS_LEN: usize = 20;
+/// enough for hexadecimal, the biggest base
+const MAX_BASE: usize = 16;
+
+/// Everything the mistakes page shows
+#[derive(Debug, Clone, Default)]
+pub struct MistakeStats {
+    /// number of mistakes for each index of the digits with at least one mistake
+    pub errors_per_position: BTreeMap<usize, u32>,
+    /// confusion_matrix[expected][typed], only the first `base` rows and columns are used
+    pub confusion_matrix: [[u32; MAX_BASE]; MAX_BASE],
+    pub base: u8,
+    /// the same mistakes made at the same position, most frequent first
+    pub common_confusions: Vec<(Mistake, u32)>,
+}
+
+/// Every wrong digit the user typed, oldest first
+#[derive(Debug)]
+pub struct MistakeLog {
+    sequence: Sequence,
+    base: u8,
+    mistakes: Vec<Mistake>,
+}
+
+/// one line per mistake: "index expected typed"
+fn to_line(mistake: &Mistake) -> String {
+    format!("{} {} {}", mistake.index, mistake.expected, mistake.typed)
+}
+
+fn from_line(line: &str, base: u8) -> Result<Mistake, ()> {
+    let mut fields = line.split_whitespace();
+    let mut next_field = || fields.next().ok_or(());
+
+    let index = next_field()?.parse::<usize>().map_err(|_| ())?;
+    let expected = next_field()?.parse::<u8>().map_err(|_| ())?;
+    let typed = next_field()?.parse::<u8>().map_err(|_| ())?;
+    if expected >= base || typed >= base {
+        return Err(()); // data corrupted
+    }
+
+    Ok(Mistake {
+        index,
+        expected,
+        typed,
+    })
+}
+
+/// the log has no dates, so each mistake is kept as often as in the log where it is the most frequent
+fn merge_mistakes(mistakes: &mut Vec<Mistake>, content: &str, base: u8) -> usize {
+    let key = |m: &Mistake| (m.index, m.expected, m.typed);
+    let mut counts: HashMap<(usize, u8, u8), usize> = HashMap::new();
+    for mistake in mistakes.iter() {
+        *counts.entry(key(mistake)).or_default() += 1;
+    }
+
+    let len = mistakes.len();
+    let mut other_counts: HashMap<(usize, u8, u8), usize> = HashMap::new();
+    for mistake in content
+        .lines()
+        .filter_map(|line| from_line(line, base).ok())
+    {
+        let other_count = other_counts.entry(key(&mistake)).or_default();
+        *other_count += 1;
+        let count = counts.entry(key(&mistake)).or_default();
+        if *other_count > *count {
+            mistakes.push(mistake);
+            *count += 1;
+        }
+    }
+    mistakes.len() - len
+}
+
+impl MistakeLog {
+    pub fn load(sequence: Sequence, base: u8) -> Self {
+        let mistakes_file_path = match get_stats_file_path(sequence, base, MISTAKES_FILE_NAME) {
+            Ok(path) => path,
+            Err(_) => {
+                return Self {
+                    sequence,
+                    base,
+                    mistakes: vec![],
+                }
+            }
+        };
+        let content = fs::read_to_string(mistakes_file_path).unwrap_or_default();
+
+        let mistakes = content
+            .lines()
+            .filter_map(|line| from_line(line, base).ok())
+            .collect();
+        Self {
+            sequence,
+            base,
+            mistakes,
+        }
+    }
+
+    pub fn stats(&self) -> MistakeStats {
+        MistakeStats {
+            errors_per_position: self.errors_per_position(),
+            confusion_matrix: self.confusion_matrix(),
+            base: self.base,
+            common_confusions: self.common_confusions(COMMON_CONFUSIONS_LEN),
+        }
+    }
+
+    fn errors_per_position(&self) -> BTreeMap<usize, u32> {
+        let mut errors = BTreeMap::new();
+        for mistake in &self.mistakes {
+            *errors.entry(mistake.index).or_default() += 1;
+        }
+        errors
+    }
+
+    fn confusion_matrix(&self) -> [[u32; MAX_BASE]; MAX_BASE] {
+        let mut matrix = [[0; MAX_BASE]; MAX_BASE];
+        for mistake in &self.mistakes {
+            matrix[mistake.expected as usize][mistake.typed as usize] += 1;
+        }
+        matrix
+    }
+
+    fn common_confusions(&self, len: usize) -> Vec<(Mistake, u32)> {
+        let mut counts: HashMap<(usize, u8, u8), u32> = HashMap::new();
+        for m in &self.mistakes {
+            *counts.entry((m.index, m.expected, m.typed)).or_default() += 1;
+        }
+
+        let mut confusions = counts
+            .into_iter()
+            .map(|((index, expected, typed), count)| {
+                let mistake = Mistake {
+                    index,
+                    expected,
+                    typed,
+                };
+                (mistake, count)
+            })
+            .collect::<Vec<_>>();
+        // most frequent first, then by position
+        confusions
+            .sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.index.cmp(&b.index)));
+        confusions.truncate(len);
+        confusions
+    }
+
+    /// add the mistakes of another mistakes file, return how many were added
+    pub fn merge(&mut self, content: &str) -> Result<usize, StorageError> {
+        let added = merge_mistakes(&mut self.mistakes, content, self.base);
+
+        let mistakes_file_path = get_stats_file_path(self.sequence, self.base, MISTAKES_FILE_NAME)?;
+        let content = self
+            .mistakes
+            .iter()
+            .map(|mistake| to_line(mistake) + "\n")
+            .collect::<String>();
+        fs::write(mistakes_file_path, content)?;
+        Ok(added)
+    }
+
+    /// save the mistake in memory and append it to the mistakes file
+    pub fn push(&mut self, mistake: Mistake) -> Result<(), StorageError> {
+        self.mistakes.push(mistake);
+
+        let mistakes_file_path = get_stats_file_path(self.sequence, self.base, MISTAKES_FILE_NAME)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(mistakes_file_path)?;
+        writeln!(file, "{}", to_line(&mistake))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(mistakes: Vec<Mistake>) -> MistakeLog {
+        MistakeLog {
+            sequence: Sequence::Pi,
+            base: 10,
+            mistakes,
+        }
+    }
+
+    fn mistake(index: usize, expected: u8, typed: u8) -> Mistake {
+        Mistake {
+            index,
+            expected,
+            typed,
+        }
+    }
+
+    #[test]
+    fn mistakes_round_trip() {
+        let recorded = mistake(1_999_999, 9, 0);
+        assert_eq!(from_line(&to_line(&recorded), 10), Ok(recorded));
+        // not a digit of the base
+        assert!(from_line("12 9 10", 10).is_err());
+        assert_eq!(from_line("12 9 10", 16), Ok(mistake(12, 9, 10)));
+        assert!(from_line("12 9", 10).is_err());
+    }
+
+    #[test]
+    fn stats_count_the_mistakes() {
+        let log = log(vec![
+            mistake(5, 3, 8),
+            mistake(1_500_000, 2, 7),
+            mistake(5, 3, 8),
+            mistake(5, 3, 1),
+            mistake(40, 3, 8),
+        ]);
+        let stats = log.stats();
+
+        assert_eq!(
+            stats.errors_per_position,
+            BTreeMap::from([(5, 3), (40, 1), (1_500_000, 1)])
+        );
+        assert_eq!(stats.confusion_matrix[3][8], 3);
+        assert_eq!(stats.confusion_matrix[3][1], 1);
+        assert_eq!(stats.confusion_matrix[2][7], 1);
+        assert_eq!(stats.confusion_matrix.iter().flatten().sum::<u32>(), 5);
+        // most frequent first, then by position
+        assert_eq!(
+            stats.common_confusions,
+            vec![
+                (mistake(5, 3, 8), 2),
+                (mistake(5, 3, 1), 1),
+                (mistake(40, 3, 8), 1),
+                (mistake(1_500_000, 2, 7), 1),
+            ]
+        );
+        assert_eq!(log.common_confusions(1), vec![(mistake(5, 3, 8), 2)]);
+    }
+
+    #[test]
+    fn merging_twice_adds_nothing() {
+        let mistake = |index, typed| mistake(index, 1, typed);
+        let mut mistakes = vec![mistake(3, 2), mistake(7, 0)];
+        let other = "3 1 2\n3 1 2\n9 1 5\nnot a mistake\n";
+
+        assert_eq!(merge_mistakes(&mut mistakes, other, 10), 2);
+        assert_eq!(merge_mistakes(&mut mistakes, other, 10), 0);
+        assert_eq!(
+            mistakes,
+            vec![mistake(3, 2), mistake(7, 0), mistake(3, 2), mistake(9, 5)]
+        );
+    }
+}
@@ -1,17 +1,39 @@
+pub mod archive;
+pub mod history;
+pub mod mistakes;
 pub mod preferences;
+pub mod saved_game;
+pub mod srs;
+pub mod storage;
+pub mod transfer;
 
-use std::time::Duration;
+use std::{path::PathBuf, thread, time::Duration};
 
 use crate::{
     components::{
         about::{AboutInput, AboutPageModel},
         header::{HeaderModel, HeaderOutput},
-        pi_digit::{PiDigitInput, PiDigitModel, PiDigitState},
+        mistakes::{MistakesPageInput, MistakesPageModel},
+        pi_digit::{PiDigitInput, PiDigitModel},
         preferences::{PreferencesPageInput, PreferencesPageModel, PreferencesPageOutput},
+        profiles::{ProfilesPageInput, ProfilesPageModel, ProfilesPageOutput},
+        search::{SearchPageInput, SearchPageModel, SearchPageOutput},
+        statistics::{StatisticsPageInput, StatisticsPageModel},
     },
     config,
+    digits::{
+        self,
+        custom::{self, ImportError},
+        digit_char, Digits, DigitsError, Sequence,
+    },
+    game::{
+        srs::Scheduler, AppMode, GameEvent, GameInput, GameOverReason, GameSession, GameSummary,
+        SavedGame,
+    },
 };
 use adw::prelude::*;
+use history::{History, SessionRecord};
+use mistakes::MistakeLog;
 use preferences::AppPreferences;
 use relm4::{
     abstractions::Toaster,
@@ -21,36 +43,15 @@ use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
     SimpleComponent,
 };
-
-// include pi digits into the binary (1 million digits)
-const PI_DIGITS: &[u8; 1_000_000] = include_bytes!("../../data/app/1m");
+use storage::StorageError;
 
 // App Utils
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum AppMode {
-    Blind,
-    Learn,
-    InstantDeath,
-}
-
-impl TryFrom<u8> for AppMode {
-    type Error = ();
-
-    fn try_from(val: u8) -> Result<Self, Self::Error> {
-        match val {
-            0 => Ok(AppMode::Blind),
-            1 => Ok(AppMode::Learn),
-            2 => Ok(AppMode::InstantDeath),
-            _ => Err(()),
-        }
-    }
-}
-
 #[derive(Debug, PartialEq)]
 pub enum AppPages {
     Placeholder,
     Memoriser,
+    Results,
 }
 
 macro_rules! push_toast {
@@ -62,6 +63,14 @@ macro_rules! push_toast {
     };
 }
 
+/// log an error and tell the user what failed
+macro_rules! report_error {
+    ($what:expr, $err:expr, $sender:expr) => {{
+        gtk::glib::g_warning!(config::LOG_DOMAIN, "{}: {}", $what, $err);
+        push_toast!(format!("{}: {}", $what, $err), 3, $sender)
+    }};
+}
+
 /// Simple macro to return when None (tor = try or return)
 macro_rules! tor {
     ($expr:expr) => {
@@ -75,14 +84,31 @@ macro_rules! tor {
 // App Component
 
 pub struct AppModel {
-    curr_pi_index: usize,
+    game: GameSession,
+    digits: Digits,
+    /// digits are being computed in the background
+    loading_digits: bool,
+    /// shown in the game title, cached since custom sequences are read from disk
+    sequence_symbol: String,
     preferences: AppPreferences,
-    timeout_progress: f64,
+    /// start offset of the next game, defaults to the preference
+    launch_offset: usize,
+    last_game: Option<GameSummary>,
+    is_personal_best: bool,
+    history: History,
+    mistakes: MistakeLog,
+    scheduler: Scheduler,
+    /// game left running with the current sequence and base, offered on the welcome page
+    saved_game: Option<SavedGame>,
 
     // components
     header: Controller<HeaderModel>,
     about_page: Controller<AboutPageModel>,
     preferences_page: Controller<PreferencesPageModel>,
+    statistics_page: Controller<StatisticsPageModel>,
+    mistakes_page: Controller<MistakesPageModel>,
+    search_page: Controller<SearchPageModel>,
+    profiles_page: Controller<ProfilesPageModel>,
     toaster: Toaster,
     current_page: AppPages,
 
@@ -91,18 +117,403 @@ pub struct AppModel {
 
     // other
     timeout_ticker: Option<SourceId>,
+    /// parent of the file dialogs
+    window: gtk::Window,
 }
 
 impl AppModel {
-    fn reset_digits(&mut self) {
+    /// throw away the current game and prepare a new one with the current preferences
+    fn reset_game(&mut self) {
+        self.clear_ticker();
         self.pi_digits.guard().clear();
-        self.curr_pi_index = 0;
+        let mut settings = self
+            .preferences
+            .game_settings(self.digits.clone(), self.launch_offset);
+        if self.preferences.mode == AppMode::Review {
+            settings.review_queue = self.scheduler.due_chunks(srs::today(), self.digits.len());
+        }
+        self.game = GameSession::new(settings);
     }
 
-    fn spawn_ticker(&mut self, tick_sender: ComponentSender<Self>) {
-        if self.preferences.timeout.is_none() {
+    /// keep the running game to resume it later, it replaces the previously saved one
+    fn save_game(&mut self) -> Result<(), StorageError> {
+        if !self.game.has_started() || self.game.is_over() {
+            return Ok(()); // nothing worth resuming
+        }
+        let saved = self.game.save();
+        let result = saved.save(self.preferences.sequence, self.preferences.base);
+        self.saved_game = Some(saved);
+        result
+    }
+
+    fn start_game(&mut self, sender: &ComponentSender<Self>) {
+        self.reset_game();
+        if self.game.review().is_some_and(|r| r.remaining() == 0) {
+            push_toast!("Nothing to review today", 2, sender);
+            return sender.input(AppInput::SwitchPage(AppPages::Placeholder));
+        }
+        let events = self.game.start();
+        self.render(events, sender);
+    }
+
+    /// reflect the game state changes into the view
+    fn render(&mut self, events: Vec<GameEvent>, sender: &ComponentSender<Self>) {
+        for event in events {
+            match event {
+                GameEvent::Push(digit, state) => {
+                    self.pi_digits.guard().push_back((
+                        digit,
+                        state,
+                        self.preferences.digits_per_row,
+                    ));
+                }
+                GameEvent::Update(index, digit, state) => {
+                    self.pi_digits
+                        .guard()
+                        .send(index, PiDigitInput::UpdateDigitState((digit, state)));
+                }
+                GameEvent::Pop => {
+                    self.pi_digits.guard().pop_back();
+                }
+                GameEvent::Clear => self.pi_digits.guard().clear(),
+                GameEvent::LapCompleted(lap) => {
+                    if lap.widened {
+                        push_toast!(
+                            format!(
+                                "Drill window widened by {} digits",
+                                config::DRILL_WIDEN_STEP
+                            ),
+                            2,
+                            sender
+                        );
+                    } else if !lap.clean {
+                        push_toast!("Lap completed with mistakes, streak lost", 2, sender);
+                    }
+                }
+                GameEvent::RestartTimer => {
+                    self.clear_ticker(); // restart timer
+                    self.spawn_ticker(sender.clone());
+                }
+                GameEvent::Mistake(mistake) => {
+                    if let Err(err) = self.mistakes.push(mistake) {
+                        report_error!("Failed to save the mistake", err, sender);
+                    }
+                }
+                GameEvent::ChunkReviewed(review) => {
+                    self.scheduler
+                        .grade(review.chunk, review.grade, srs::today());
+                    let (sequence, base) = (self.preferences.sequence, self.preferences.base);
+                    if let Err(err) = self.scheduler.save(sequence, base) {
+                        report_error!("Failed to save the review schedule", err, sender);
+                    }
+                }
+                GameEvent::QuestionAnswered(result) => {
+                    if result.errors > 0 {
+                        let end = (result.position + self.game.quiz().map_or(0, |q| q.length()))
+                            .min(self.digits.len());
+                        let answer = self.digits[result.position..end]
+                            .iter()
+                            .map(|d| digit_char(*d))
+                            .collect::<String>();
+                        push_toast!(
+                            format!("Digits {} to {} were {answer}", result.position + 1, end),
+                            3,
+                            sender
+                        );
+                    }
+                }
+                GameEvent::GameOver(summary) => {
+                    self.clear_ticker();
+
+                    let best = self
+                        .history
+                        .personal_best(summary.mode, summary.timeout)
+                        .unwrap_or_default();
+                    self.is_personal_best = summary.result() > best;
+                    if let Err(err) = self.history.push(SessionRecord::new(&summary)) {
+                        report_error!("Failed to save the game in the history", err, sender);
+                    }
+
+                    self.last_game = Some(summary);
+                    sender.input(AppInput::SwitchPage(AppPages::Results));
+                }
+            }
+        }
+    }
+
+    fn game_hint(&self) -> String {
+        if let Some(drill) = self.game.drill() {
+            return format!(
+                "(Digits {} to {}, lap {}, clean streak: {})",
+                drill.start() + 1,
+                drill.end(),
+                drill.lap(),
+                drill.streak()
+            );
+        }
+        if let Some(quiz) = self.game.quiz() {
+            let position = quiz.current_question().unwrap_or_default();
+            return format!(
+                "(Digits {} to {}, question {}/{}, score: {})",
+                position + 1,
+                position + quiz.length(),
+                quiz.question_number(),
+                quiz.total(),
+                quiz.score()
+            );
+        }
+        if let Some(cloze) = self.game.cloze() {
+            return format!(
+                "(Fill the gaps of digits {} to {}, {} of {} done)",
+                cloze.start() + 1,
+                cloze.end(),
+                cloze.filled(),
+                cloze.gap_count()
+            );
+        }
+        if let Some(reverse) = self.game.reverse() {
+            return format!(
+                "(Digits {} down to {}, {} typed)",
+                reverse.end(),
+                reverse.start() + 1,
+                reverse.typed()
+            );
+        }
+        if let Some(choice) = self.game.choice() {
+            return format!(
+                "(Which digits come after digit #{}? Press 1 to {})",
+                choice.position(),
+                choice.candidates().len()
+            );
+        }
+        if let Some(review) = self.game.review() {
+            return format!(
+                "(Chunk #{}, {} left to review today)",
+                review.current_chunk().unwrap_or_default() + 1,
+                review.remaining()
+            );
+        }
+        "(Start typing the digits 🖮)".to_string()
+    }
+
+    /// label of a candidate button in choice mode, e.g. "1. 5358"
+    fn choice_label(&self, candidate: usize) -> String {
+        let digits = match self
+            .game
+            .choice()
+            .and_then(|c| c.candidates().get(candidate))
+        {
+            Some(digits) => digits.iter().map(|d| digit_char(*d)).collect::<String>(),
+            None => return String::new(),
+        };
+        format!("{}. {digits}", candidate + 1)
+    }
+
+    fn results_description(&self) -> String {
+        let summary = match self.last_game {
+            Some(summary) => summary,
+            None => return String::new(),
+        };
+
+        let cause = match (summary.reason, summary.typed) {
+            (GameOverReason::WrongDigit, Some(typed)) => format!(
+                "Digit #{} was a {} but you typed a {}",
+                summary.failing_index + 1,
+                digit_char(summary.expected),
+                digit_char(typed)
+            ),
+            (GameOverReason::Timeout, _) => format!(
+                "Time ran out on digit #{}, it was a {}",
+                summary.failing_index + 1,
+                digit_char(summary.expected)
+            ),
+            (GameOverReason::Completed, _) if summary.mode == AppMode::Review => {
+                "All the due chunks are reviewed, see you tomorrow".to_string()
+            }
+            (GameOverReason::Completed, _) if summary.mode == AppMode::Quiz => {
+                "All the questions are answered".to_string()
+            }
+            (GameOverReason::Completed, _) if summary.mode == AppMode::Cloze => {
+                "All the gaps are filled".to_string()
+            }
+            (GameOverReason::Completed, _) if summary.mode == AppMode::Reverse => {
+                "You made it back to the first digit".to_string()
+            }
+            (GameOverReason::Completed, _) => {
+                "You reached the end of the known digits, congratulations!".to_string()
+            }
+            _ => format!(
+                "You stopped before digit #{} with {} error(s)",
+                summary.failing_index + 1,
+                summary.errors
+            ),
+        };
+        let mut description = format!(
+            "{cause}\n\n{} digits reached in {:.1}s ({:.1} digits per minute)",
+            summary.digits_reached,
+            summary.elapsed.as_secs_f64(),
+            summary.digits_per_minute()
+        );
+        if summary.mode == AppMode::Drill {
+            description += &format!("\nBest clean streak: {}", summary.best_streak);
+        }
+        if summary.mode == AppMode::Choice {
+            description += &format!(
+                "\nMean reaction time: {:.1}s",
+                summary.reaction_time.as_secs_f64()
+            );
+        }
+        if let Some(quiz) = self.game.quiz() {
+            description += &format!("\nScore: {} out of {}", summary.score, quiz.max_score());
+        }
+        description
+    }
+
+    /// make the digits of the preferred sequence available, computing them in the background if needed
+    fn load_digits(&mut self, sender: &ComponentSender<Self>) {
+        if self.loading_digits {
+            return; // the new preferences are picked up when the running computation ends
+        }
+
+        let (sequence, base, len) = (
+            self.preferences.sequence,
+            self.preferences.base,
+            self.preferences.digits_len,
+        );
+        if sequence == Sequence::Pi && base == 10 && len <= digits::PI_DIGITS.len() {
+            let loaded = digits::load_digits(sequence, len, base);
+            return sender.input(AppInput::DigitsLoaded((sequence, base, len, loaded)));
+        }
+
+        self.loading_digits = true;
+        push_toast!(
+            format!("Loading the digits of {}...", sequence.get().name()),
+            2,
+            sender
+        );
+        let loaded_sender = sender.clone();
+        thread::spawn(move || {
+            let loaded = digits::load_digits(sequence, len, base);
+            loaded_sender.input(AppInput::DigitsLoaded((sequence, base, len, loaded)));
+        });
+    }
+
+    /// switch to the preferred sequence and base, they have their own history and stats
+    fn load_sequence(&mut self, sender: &ComponentSender<Self>) {
+        let (sequence, base) = (self.preferences.sequence, self.preferences.base);
+        self.sequence_symbol = sequence.get().symbol().to_string();
+        self.history = History::load(sequence, base);
+        self.mistakes = MistakeLog::load(sequence, base);
+        self.scheduler = Scheduler::load(sequence, base);
+        self.saved_game = None; // loaded with the digits
+        self.load_digits(sender);
+    }
+
+    /// the game title, e.g. "Learn PI! 👨‍🎓"
+    fn game_title(&self) -> String {
+        self.mode_title(self.preferences.mode)
+    }
+
+    fn mode_title(&self, mode: AppMode) -> String {
+        let symbol = &self.sequence_symbol;
+        match mode {
+            AppMode::Blind => format!("Blind {symbol} 😵‍💫"),
+            AppMode::Learn => format!("Learn {symbol}! 👨‍🎓"),
+            AppMode::InstantDeath => format!("Instant Death {symbol} ☠️"),
+            AppMode::Drill => format!("Drill {symbol} 🔁"),
+            AppMode::Review => format!("Review {symbol} 🗓️"),
+            AppMode::Quiz => format!("Quiz {symbol} ❓"),
+            AppMode::Cloze => format!("Cloze {symbol} 🕳️"),
+            AppMode::Reverse => format!("Reverse {symbol} ⏪"),
+            AppMode::Choice => format!("Choice {symbol} 🔢"),
+        }
+    }
+
+    /// e.g. "Blind π 😵‍💫 from digit 1, 12 min played"
+    fn saved_game_description(&self) -> String {
+        let saved = match &self.saved_game {
+            Some(saved) => saved,
+            None => return String::new(),
+        };
+        let seconds = saved.elapsed.as_secs();
+        let played = match seconds < 60 {
+            true => format!("{seconds} s"),
+            false => format!("{} min", seconds / 60),
+        };
+        format!(
+            "{} from digit {}, {played} played",
+            self.mode_title(saved.settings.mode),
+            saved.settings.start_index + 1
+        )
+    }
+
+    /// the loaded preferences, the defaults if they could not be read
+    fn preferences_or_default(
+        loaded_preferences: Result<AppPreferences, StorageError>,
+        sender: &ComponentSender<Self>,
+    ) -> AppPreferences {
+        match loaded_preferences {
+            Ok(preferences) => preferences,
+            Err(StorageError::Corrupted) => {
+                let text = "The preferences were unreadable and have been reset, the old file is kept with a .bak extension";
+                gtk::glib::g_warning!(config::LOG_DOMAIN, "{}", text);
+                push_toast!(text, 5, sender);
+                AppPreferences::default()
+            }
+            Err(StorageError::Newer) => {
+                let text = "The preferences were saved by a newer version of the app, the defaults are used and they can not be changed";
+                gtk::glib::g_warning!(config::LOG_DOMAIN, "{}", text);
+                push_toast!(text, 5, sender);
+                AppPreferences::default()
+            }
+            Err(err) => {
+                report_error!("Failed to load the preferences", err, sender);
+                AppPreferences::default()
+            }
+        }
+    }
+
+    /// play with the preferences and stats of another profile
+    fn switch_profile(&mut self, profile: &str, sender: &ComponentSender<Self>) {
+        if let Err(err) = self.save_game() {
+            report_error!("Failed to save the running game", err, sender);
+        }
+        if let Err(err) = storage::switch_profile(profile) {
+            return report_error!(format!("Failed to switch to {profile}"), err, sender);
+        }
+        self.reload_profile(sender);
+        push_toast!(format!("Switched to {profile}"), 2, sender);
+    }
+
+    /// read again the preferences and the stats of the active profile, back to the welcome page
+    fn reload_profile(&mut self, sender: &ComponentSender<Self>) {
+        self.preferences = Self::preferences_or_default(AppPreferences::load(), sender);
+        self.launch_offset = self.preferences.start_offset;
+        let _ = self
+            .preferences_page
+            .sender()
+            .send(PreferencesPageInput::SyncPreferences(self.preferences));
+        self.current_page = AppPages::Placeholder;
+        self.reset_game();
+        self.load_sequence(sender);
+    }
+
+    /// change the preferred mode from the app, e.g. to drill a search result
+    fn sync_mode(&mut self, mode: AppMode, sender: &ComponentSender<Self>) {
+        if self.preferences.mode == mode {
             return;
         }
+        self.preferences.mode = mode;
+        let _ = self
+            .preferences_page
+            .sender()
+            .send(PreferencesPageInput::SyncMode(mode));
+        if let Err(err) = AppPreferences::set(self.preferences) {
+            report_error!("Failed to save the preferences", err, sender);
+        }
+    }
+
+    fn spawn_ticker(&mut self, tick_sender: ComponentSender<Self>) {
         let ticker_id = gtk::glib::timeout_add_local(
             Duration::from_millis(config::PROGRESSBAR_UPDATE_FREQUENCY as u64),
             move || {
@@ -117,7 +528,6 @@ impl AppModel {
         if let Some(ticker) = self.timeout_ticker.take() {
             ticker.remove();
         }
-        self.timeout_progress = 0.0; // after ticker kill to prevent a last minute trigger
     }
 }
 
@@ -126,9 +536,23 @@ pub enum AppInput {
     KeyPressed(Key),
     AddDigit(char),
     RemoveLastDigit,
+    /// pick a candidate in choice mode
+    Choose(usize),
+    FinishGame,
+    /// continue the game saved with the current sequence and base
+    ResumeGame,
+    SetLaunchOffset(usize),
     TickTimeout,
+    /// (sequence, base, requested length, digits)
+    DigitsLoaded((Sequence, u8, usize, Result<Digits, DigitsError>)),
 
     Open(HeaderOutput),
+    Search(SearchPageOutput),
+    Profiles(ProfilesPageOutput),
+    /// write the user data to the archive at this path
+    ExportData(PathBuf),
+    /// merge the user data of the archive at this path
+    ImportData(PathBuf),
     SetPreference(PreferencesPageOutput),
     PushToast((String, Duration)),
     SwitchPage(AppPages),
@@ -136,7 +560,8 @@ pub enum AppInput {
 
 #[relm4::component(pub)]
 impl SimpleComponent for AppModel {
-    type Init = AppPreferences;
+    /// the preferences, or why they could not be loaded
+    type Init = Result<AppPreferences, StorageError>;
     type Input = AppInput;
     type Output = ();
 
@@ -164,7 +589,7 @@ impl SimpleComponent for AppModel {
                     set_hexpand: true,
                     add_css_class: "osd",
                     #[watch]
-                    set_fraction: model.timeout_progress,
+                    set_fraction: model.game.timeout_progress(),
                 },
 
                 gtk::Box {
@@ -194,12 +619,74 @@ impl SimpleComponent for AppModel {
                                     set_title: "Welcome to GnoPi!",
                                     set_description: Some("A π memorization trainer"),
 
-                                    gtk::Button {
-                                        set_css_classes: &["suggested-action", "pill"],
-                                        set_label: "Launch!",
-                                        set_use_underline: true,
+                                    gtk::Box {
+                                        set_orientation: gtk::Orientation::Vertical,
+                                        set_spacing: 10,
                                         set_halign: gtk::Align::Center,
-                                        connect_clicked => AppInput::SwitchPage(AppPages::Memoriser)
+
+                                        gtk::Button {
+                                            set_css_classes: &["suggested-action", "pill"],
+                                            set_label: "Launch!",
+                                            set_use_underline: true,
+                                            #[watch]
+                                            set_sensitive: !model.loading_digits,
+                                            set_halign: gtk::Align::Center,
+                                            connect_clicked => AppInput::SwitchPage(AppPages::Memoriser)
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["pill"],
+                                            set_label: "Resume",
+                                            #[watch]
+                                            set_visible: model.saved_game.is_some(),
+                                            #[watch]
+                                            set_sensitive: !model.loading_digits,
+                                            set_halign: gtk::Align::Center,
+                                            connect_clicked => AppInput::ResumeGame
+                                        },
+                                        gtk::Label {
+                                            set_css_classes: &["caption", "dim-label"],
+                                            #[watch]
+                                            set_visible: model.saved_game.is_some(),
+                                            #[watch]
+                                            set_label: &model.saved_game_description(),
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["flat"],
+                                            #[wrap(Some)]
+                                            set_child = &adw::ButtonContent {
+                                                set_icon_name: "avatar-default-symbolic",
+                                                #[watch]
+                                                set_label: &storage::active_profile(),
+                                            },
+                                            set_tooltip_text: Some("Switch profile"),
+                                            set_halign: gtk::Align::Center,
+                                            connect_clicked => AppInput::Open(HeaderOutput::Profiles)
+                                        },
+                                        gtk::Box {
+                                            set_orientation: gtk::Orientation::Horizontal,
+                                            set_spacing: 5,
+                                            set_halign: gtk::Align::Center,
+
+                                            gtk::Label {
+                                                set_label: "Skip the first",
+                                            },
+                                            gtk::SpinButton {
+                                                set_numeric: true,
+                                                set_digits: 0,
+                                                set_adjustment: &gtk::Adjustment::new(0.0, 0.0, config::MAX_START_OFFSET as f64, 10.0, 0.0, 0.0), // set range and step increment
+                                                // a search hit can be drilled up to the last loaded digit, set before the value not to clamp it
+                                                #[watch]
+                                                set_range: (0.0, model.digits.len().saturating_sub(1) as f64),
+                                                #[watch]
+                                                set_value: model.launch_offset as f64,
+                                                connect_value_changed[sender] => move |spin_button| {
+                                                    sender.input(AppInput::SetLaunchOffset(spin_button.value().round() as usize));
+                                                }
+                                            },
+                                            gtk::Label {
+                                                set_label: "digits",
+                                            },
+                                        },
                                     },
                                 } ,
 
@@ -210,28 +697,25 @@ impl SimpleComponent for AppModel {
 
                                     gtk::Label {
                                         #[watch]
-                                        set_label: match model.preferences.mode {
-                                            AppMode::Blind => "Blind PI 😵‍💫",
-                                            AppMode::Learn => "Learn PI! 👨‍🎓",
-                                            AppMode::InstantDeath => "Instant Death PI ☠️",
-                                        },
+                                        set_label: &model.game_title(),
                                         set_css_classes: &["title-1"],
                                         #[watch]
                                         set_margin_bottom: match model.preferences.mode {
-                                            AppMode::Blind | AppMode::InstantDeath => 5,
+                                            AppMode::Blind | AppMode::InstantDeath | AppMode::Drill | AppMode::Review | AppMode::Quiz | AppMode::Cloze | AppMode::Reverse | AppMode::Choice => 5,
                                             AppMode::Learn => 15,
                                         },
                                     },
                                     gtk::Label {
                                         #[watch]
                                         set_visible: match model.preferences.mode {
-                                            AppMode::Blind | AppMode::InstantDeath => true,
+                                            AppMode::Blind | AppMode::InstantDeath | AppMode::Drill | AppMode::Review | AppMode::Quiz | AppMode::Cloze | AppMode::Reverse | AppMode::Choice => true,
                                             AppMode::Learn => false,
                                         },
-                                        set_label: "(Start typing the digits 🖮)",
+                                        #[watch]
+                                        set_label: &model.game_hint(),
                                         #[watch]
                                         set_margin_bottom: match model.preferences.mode {
-                                            AppMode::Blind | AppMode::InstantDeath => 10,
+                                            AppMode::Blind | AppMode::InstantDeath | AppMode::Drill | AppMode::Review | AppMode::Quiz | AppMode::Cloze | AppMode::Reverse | AppMode::Choice => 10,
                                             AppMode::Learn => 0,
                                         },
                                     },
@@ -249,7 +733,7 @@ impl SimpleComponent for AppModel {
 
                                         #[watch]
                                         set_vadjustment: Some(&{
-                                            let scroll_pos = 100.0*(model.curr_pi_index as f64)/(model.preferences.digits_per_row as f64);
+                                            let scroll_pos = 100.0*(model.game.cursor() as f64)/(model.preferences.digits_per_row as f64);
                                             gtk::Adjustment::new(scroll_pos, 0.0, scroll_pos, 30.0, 0.0, 0.0)
                                         }),
 
@@ -259,8 +743,95 @@ impl SimpleComponent for AppModel {
                                             set_column_spacing: 5,
                                             set_row_spacing: 10,
                                         }
+                                    },
+                                    gtk::Box {
+                                        set_orientation: gtk::Orientation::Horizontal,
+                                        set_spacing: 10,
+                                        set_halign: gtk::Align::Center,
+                                        #[watch]
+                                        set_visible: model.game.choice().is_some(),
+
+                                        gtk::Button {
+                                            set_css_classes: &["pill", "title-3"],
+                                            set_focus_on_click: false,
+                                            #[watch]
+                                            set_label: &model.choice_label(0),
+                                            #[watch]
+                                            set_visible: model.game.choice().is_some_and(|c| c.candidates().len() > 0),
+                                            #[watch]
+                                            set_sensitive: !model.game.choice().is_some_and(|c| c.is_wrong(0)),
+                                            connect_clicked => AppInput::Choose(0)
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["pill", "title-3"],
+                                            set_focus_on_click: false,
+                                            #[watch]
+                                            set_label: &model.choice_label(1),
+                                            #[watch]
+                                            set_visible: model.game.choice().is_some_and(|c| c.candidates().len() > 1),
+                                            #[watch]
+                                            set_sensitive: !model.game.choice().is_some_and(|c| c.is_wrong(1)),
+                                            connect_clicked => AppInput::Choose(1)
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["pill", "title-3"],
+                                            set_focus_on_click: false,
+                                            #[watch]
+                                            set_label: &model.choice_label(2),
+                                            #[watch]
+                                            set_visible: model.game.choice().is_some_and(|c| c.candidates().len() > 2),
+                                            #[watch]
+                                            set_sensitive: !model.game.choice().is_some_and(|c| c.is_wrong(2)),
+                                            connect_clicked => AppInput::Choose(2)
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["pill", "title-3"],
+                                            set_focus_on_click: false,
+                                            #[watch]
+                                            set_label: &model.choice_label(3),
+                                            #[watch]
+                                            set_visible: model.game.choice().is_some_and(|c| c.candidates().len() > 3),
+                                            #[watch]
+                                            set_sensitive: !model.game.choice().is_some_and(|c| c.is_wrong(3)),
+                                            connect_clicked => AppInput::Choose(3)
+                                        },
+                                    },
+                                    gtk::Button {
+                                        set_css_classes: &["pill"],
+                                        set_label: "Finish",
+                                        set_halign: gtk::Align::Center,
+                                        set_margin_top: 5,
+                                        set_focus_on_click: false,
+                                        connect_clicked => AppInput::FinishGame
+                                    }
+                                },
+
+                                add_named[Some("results")] = &adw::StatusPage {
+                                    set_icon_name: Some("logo"),
+                                    #[watch]
+                                    set_title: if model.is_personal_best { "New personal best! 🏆" } else { "Game over 💀" },
+                                    #[watch]
+                                    set_description: Some(&model.results_description()),
+
+                                    gtk::Box {
+                                        set_orientation: gtk::Orientation::Horizontal,
+                                        set_spacing: 10,
+                                        set_halign: gtk::Align::Center,
+
+                                        gtk::Button {
+                                            set_css_classes: &["pill"],
+                                            set_label: "Back",
+                                            connect_clicked => AppInput::SwitchPage(AppPages::Placeholder)
+                                        },
+                                        gtk::Button {
+                                            set_css_classes: &["suggested-action", "pill"],
+                                            set_label: "Retry",
+                                            #[watch]
+                                            set_sensitive: !model.loading_digits,
+                                            connect_clicked => AppInput::SwitchPage(AppPages::Memoriser)
+                                        },
                                     }
-                                }
+                                },
                             },
                         },
                     }
@@ -271,10 +842,12 @@ impl SimpleComponent for AppModel {
 
     // Initialize the UI.
     fn init(
-        preferences: Self::Init,
+        loaded_preferences: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let preferences = Self::preferences_or_default(loaded_preferences, &sender);
+
         // components init
         let header: Controller<HeaderModel> = HeaderModel::builder()
             .launch(())
@@ -287,6 +860,22 @@ impl SimpleComponent for AppModel {
             .transient_for(&root)
             .launch(preferences)
             .forward(sender.input_sender(), AppInput::SetPreference);
+        let statistics_page = StatisticsPageModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .detach();
+        let mistakes_page = MistakesPageModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .detach();
+        let search_page = SearchPageModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), AppInput::Search);
+        let profiles_page = ProfilesPageModel::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), AppInput::Profiles);
 
         // factories
         let pi_digits = FactoryVecDeque::builder()
@@ -294,19 +883,36 @@ impl SimpleComponent for AppModel {
             .detach();
 
         // define default model
-        let model = AppModel {
-            curr_pi_index: 0,
+        let digits = Digits::embedded();
+        let mut model = AppModel {
+            game: GameSession::new(
+                preferences.game_settings(digits.clone(), preferences.start_offset),
+            ),
+            digits,
+            loading_digits: false,
+            sequence_symbol: preferences.sequence.get().symbol().to_string(),
             preferences,
+            launch_offset: preferences.start_offset,
+            last_game: None,
+            is_personal_best: false,
+            history: History::load(preferences.sequence, preferences.base),
+            mistakes: MistakeLog::load(preferences.sequence, preferences.base),
+            scheduler: Scheduler::load(preferences.sequence, preferences.base),
+            saved_game: None,
             current_page: AppPages::Placeholder,
-            timeout_progress: 0.0,
 
             header,
             about_page,
             preferences_page,
+            statistics_page,
+            mistakes_page,
+            search_page,
+            profiles_page,
             toaster: Toaster::default(),
             pi_digits,
 
             timeout_ticker: None,
+            window: root.clone(),
         };
 
         // inject to view!
@@ -315,21 +921,25 @@ impl SimpleComponent for AppModel {
 
         // Insert the macro code generation here
         let widgets = view_output!();
+        if preferences.sequence != Sequence::Pi
+            || preferences.base != 10
+            || preferences.digits_len != model.digits.len()
+        {
+            model.load_digits(&sender);
+        } else {
+            model.saved_game =
+                SavedGame::load(preferences.sequence, preferences.base, model.digits.clone());
+        }
         ComponentParts { model, widgets }
     }
 
     fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
         match message {
             AppInput::TickTimeout => {
-                if let Some(timeout) = self.preferences.timeout {
-                    let incremental_step =
-                        config::PROGRESSBAR_UPDATE_FREQUENCY as f64 / timeout.as_millis() as f64;
-                    self.timeout_progress += incremental_step;
-                    if self.timeout_progress >= 1.0 {
-                        // game over, reset game
-                        sender.input(AppInput::SwitchPage(AppPages::Placeholder));
-                    }
-                }
+                let events = self.game.handle(GameInput::Tick(Duration::from_millis(
+                    config::PROGRESSBAR_UPDATE_FREQUENCY as u64,
+                )));
+                self.render(events, &sender);
             }
             AppInput::KeyPressed(key) => {
                 let key_name = tor!(key.name());
@@ -342,81 +952,106 @@ impl SimpleComponent for AppModel {
                 }
             }
             AppInput::AddDigit(character) => {
-                if self.current_page != AppPages::Memoriser || !character.is_numeric() {
+                if self.current_page != AppPages::Memoriser {
                     return;
                 }
-                let digit = tor!(character.to_digit(10)) as u8;
-                let state = if digit == PI_DIGITS[self.curr_pi_index] {
-                    PiDigitState::Right
-                } else {
-                    PiDigitState::Wrong
-                };
-
-                if self.preferences.mode == AppMode::InstantDeath && state == PiDigitState::Wrong {
-                    // game over, reset game
-                    return sender.input(AppInput::SwitchPage(AppPages::Placeholder));
+                if self.game.choice().is_some() {
+                    // the keys 1 to 4 pick the candidates
+                    let candidate = tor!(character.to_digit(10).and_then(|n| n.checked_sub(1)));
+                    return sender.input(AppInput::Choose(candidate as usize));
                 }
-                if state == PiDigitState::Right {
-                    self.clear_ticker(); // restart timer
-                    self.spawn_ticker(sender.clone())
+                // letters are digits too past base 10
+                let digit = tor!(character.to_digit(self.digits.base() as u32)) as u8;
+                let events = self.game.handle(GameInput::Digit(digit));
+                self.render(events, &sender);
+            }
+            AppInput::Choose(candidate) => {
+                if self.current_page != AppPages::Memoriser {
+                    return;
                 }
-
-                match self.preferences.mode {
-                    AppMode::Blind | AppMode::InstantDeath => {
-                        self.pi_digits.guard().push_back((
-                            digit,
-                            state,
-                            self.preferences.digits_per_row,
-                        ));
-                    }
-                    AppMode::Learn => {
-                        // update digit
-                        self.pi_digits.guard().send(
-                            self.curr_pi_index,
-                            PiDigitInput::UpdateDigitState((digit, state)),
-                        );
-
-                        // add next visible digit
-                        if self
-                            .pi_digits
-                            .guard()
-                            .get(self.curr_pi_index + config::PRELOADED_DIGITS)
-                            .is_none()
-                        {
-                            self.pi_digits.guard().push_back((
-                                PI_DIGITS[self.curr_pi_index + config::PRELOADED_DIGITS],
-                                PiDigitState::Placeholder,
-                                self.preferences.digits_per_row,
-                            ));
-                        }
-                    }
-                };
-
-                self.curr_pi_index += 1
+                let events = self.game.handle(GameInput::Choose(candidate));
+                self.render(events, &sender);
             }
             AppInput::RemoveLastDigit => {
                 if self.current_page != AppPages::Memoriser {
                     return;
                 }
-                match self.preferences.mode {
-                    AppMode::Blind => {
-                        tor!(self.pi_digits.guard().pop_back());
-                        self.curr_pi_index = self.curr_pi_index.saturating_sub(1);
+                let events = self.game.handle(GameInput::Backspace);
+                self.render(events, &sender);
+            }
+            AppInput::SetLaunchOffset(offset) => self.launch_offset = offset,
+            AppInput::DigitsLoaded((sequence, base, len, result)) => {
+                let was_loading = std::mem::take(&mut self.loading_digits);
+                let preferences = &self.preferences;
+                if (sequence, base, len)
+                    != (
+                        preferences.sequence,
+                        preferences.base,
+                        preferences.digits_len,
+                    )
+                {
+                    return self.load_digits(&sender); // changed during the computation
+                }
+
+                let name = sequence.get().name().to_string();
+                match result {
+                    Ok(digits) => {
+                        if was_loading {
+                            push_toast!(
+                                format!("{} digits of {name} ready", digits.len()),
+                                2,
+                                sender
+                            );
+                        }
+                        self.saved_game = SavedGame::load(sequence, base, digits.clone());
+                        self.digits = digits;
+
+                        // reset game state
+                        match self.current_page {
+                            AppPages::Memoriser => self.start_game(&sender),
+                            _ => self.reset_game(),
+                        }
                     }
-                    AppMode::Learn => {
-                        // remove last digit of the user
-                        self.curr_pi_index = self.curr_pi_index.saturating_sub(1);
-                        // and add right one
-                        self.pi_digits.guard().send(
-                            self.curr_pi_index,
-                            PiDigitInput::UpdateDigitState((
-                                PI_DIGITS[self.curr_pi_index],
-                                PiDigitState::Placeholder,
-                            )),
-                        );
+                    Err(err) => {
+                        report_error!(format!("Failed to load the digits of {name}"), err, sender)
+                    }
+                }
+            }
+            AppInput::FinishGame => {
+                if !self.game.has_started() {
+                    // nothing worth saving
+                    return sender.input(AppInput::SwitchPage(AppPages::Placeholder));
+                }
+                let events = self.game.handle(GameInput::Finish);
+                self.render(events, &sender);
+            }
+            AppInput::ResumeGame => {
+                let saved = tor!(self.saved_game.clone());
+                let mode = saved.settings.mode;
+                let (game, events) = match GameSession::resume(saved) {
+                    Ok(resumed) => resumed,
+                    Err(_) => {
+                        return push_toast!(
+                            "The saved game does not fit the current digits",
+                            2,
+                            sender
+                        )
                     }
-                    _ => {}
                 };
+                // the game is saved again if it is left running
+                self.saved_game = None;
+                if let Err(err) =
+                    SavedGame::delete(self.preferences.sequence, self.preferences.base)
+                {
+                    report_error!("Failed to delete the saved game", err, sender);
+                }
+
+                self.sync_mode(mode, &sender);
+                self.clear_ticker();
+                self.pi_digits.guard().clear();
+                self.game = game;
+                self.current_page = AppPages::Memoriser;
+                self.render(events, &sender);
             }
             AppInput::Open(HeaderOutput::About) => {
                 if self.about_page.sender().send(AboutInput::Show).is_err() {
@@ -433,26 +1068,239 @@ impl SimpleComponent for AppModel {
                     push_toast!("Failed to open preference page", 2, sender);
                 }
             }
+            AppInput::Open(HeaderOutput::Statistics) => {
+                if self
+                    .statistics_page
+                    .sender()
+                    .send(StatisticsPageInput::Show(self.history.daily_stats()))
+                    .is_err()
+                {
+                    push_toast!("Failed to open statistics page", 2, sender);
+                }
+            }
+            AppInput::Open(HeaderOutput::Mistakes) => {
+                if self
+                    .mistakes_page
+                    .sender()
+                    .send(MistakesPageInput::Show((
+                        self.mistakes.stats(),
+                        self.digits.clone(),
+                        self.preferences.digits_per_row,
+                    )))
+                    .is_err()
+                {
+                    push_toast!("Failed to open mistakes page", 2, sender);
+                }
+            }
+            AppInput::Open(HeaderOutput::Profiles) => {
+                if self
+                    .profiles_page
+                    .sender()
+                    .send(ProfilesPageInput::Show)
+                    .is_err()
+                {
+                    push_toast!("Failed to open profiles page", 2, sender);
+                }
+            }
+            AppInput::Profiles(ProfilesPageOutput::Switch(profile)) => {
+                self.switch_profile(&profile, &sender)
+            }
+            AppInput::Profiles(ProfilesPageOutput::Create(profile)) => {
+                if let Err(err) = storage::create_profile(&profile) {
+                    return report_error!(format!("Failed to create {profile}"), err, sender);
+                }
+                self.switch_profile(&profile, &sender);
+            }
+            AppInput::Open(HeaderOutput::Export) => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Export data")
+                    .initial_name(format!("gnopi-{}.tar", storage::active_profile()))
+                    .modal(true)
+                    .build();
+                dialog.save(
+                    Some(&self.window),
+                    None::<&gtk::gio::Cancellable>,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            sender.input(AppInput::ExportData(path));
+                        }
+                    },
+                );
+            }
+            AppInput::Open(HeaderOutput::Import) => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Import data")
+                    .modal(true)
+                    .build();
+                dialog.open(
+                    Some(&self.window),
+                    None::<&gtk::gio::Cancellable>,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            sender.input(AppInput::ImportData(path));
+                        }
+                    },
+                );
+            }
+            AppInput::ExportData(path) => match transfer::export(&path, self.preferences) {
+                Ok(()) => push_toast!("Data exported", 2, sender),
+                Err(err) => report_error!("Failed to export the data", err, sender),
+            },
+            AppInput::ImportData(path) => {
+                // the imported history may be for the running sequence
+                if let Err(err) = self.save_game() {
+                    report_error!("Failed to save the running game", err, sender);
+                }
+                let result = transfer::import(&path);
+                // part of the data may have been merged before an error
+                self.reload_profile(&sender);
+                match result {
+                    // the mistake log has no dates to tell the games apart
+                    Ok(summary) => push_toast!(
+                        format!(
+                            "Data imported: {} new games, {} new mistakes (the same mistakes made on both computers are not added up)",
+                            summary.games, summary.mistakes
+                        ),
+                        5,
+                        sender
+                    ),
+                    Err(err) => report_error!("Failed to import the data", err, sender),
+                }
+            }
+            AppInput::Open(HeaderOutput::Search) => {
+                if self
+                    .search_page
+                    .sender()
+                    .send(SearchPageInput::Show((
+                        self.digits.clone(),
+                        self.preferences.digits_per_row,
+                    )))
+                    .is_err()
+                {
+                    push_toast!("Failed to open search page", 2, sender);
+                }
+            }
+            AppInput::Search(SearchPageOutput::Drill(position)) => {
+                if let Err(err) = self.save_game() {
+                    report_error!("Failed to save the running game", err, sender);
+                }
+                self.launch_offset = position;
+                self.sync_mode(AppMode::Drill, &sender);
+                sender.input(AppInput::SwitchPage(AppPages::Memoriser));
+            }
             AppInput::SetPreference(new_pref) => {
+                if let PreferencesPageOutput::SetMode(_)
+                | PreferencesPageOutput::SetDigitsLen(_)
+                | PreferencesPageOutput::SetSequence(_)
+                | PreferencesPageOutput::SetBase(_)
+                | PreferencesPageOutput::ImportSequence(_) = new_pref
+                {
+                    // these throw the running game away
+                    if let Err(err) = self.save_game() {
+                        report_error!("Failed to save the running game", err, sender);
+                    }
+                }
                 match new_pref {
                     PreferencesPageOutput::SetMode(mode) => {
                         self.preferences.mode = mode;
 
-                        self.reset_digits(); // reset game state
-                        if mode == AppMode::Learn {
-                            PI_DIGITS
-                                .iter()
-                                .take(config::PRELOADED_DIGITS)
-                                .for_each(|d| {
-                                    self.pi_digits.guard().push_back((
-                                        *d,
-                                        PiDigitState::Placeholder,
-                                        self.preferences.digits_per_row,
-                                    ));
-                                })
+                        // reset game state
+                        match self.current_page {
+                            AppPages::Memoriser => self.start_game(&sender),
+                            _ => self.reset_game(),
                         }
                     }
-                    PreferencesPageOutput::SetTimeout(dur) => self.preferences.timeout = dur,
+                    PreferencesPageOutput::SetTimeout(dur) => {
+                        self.preferences.timeout = dur;
+                        self.game.set_timeout(dur);
+                        if dur.is_none() {
+                            self.clear_ticker();
+                        }
+                    }
+                    PreferencesPageOutput::SetStartOffset(start_offset) => {
+                        self.preferences.start_offset = start_offset;
+                        self.launch_offset = start_offset;
+                    }
+                    PreferencesPageOutput::SetDrillLength(drill_length) => {
+                        self.preferences.drill_length = drill_length
+                    }
+                    PreferencesPageOutput::SetDrillWidenAfter(widen_after) => {
+                        self.preferences.drill_widen_after = widen_after
+                    }
+                    PreferencesPageOutput::SetDigitsLen(digits_len) => {
+                        self.preferences.digits_len = digits_len;
+                        self.load_digits(&sender);
+                    }
+                    PreferencesPageOutput::SetSequence(sequence) => {
+                        self.preferences.sequence = sequence;
+                        self.load_sequence(&sender);
+                    }
+                    PreferencesPageOutput::SetBase(base) => {
+                        self.preferences.base = base;
+                        self.load_sequence(&sender);
+                    }
+                    PreferencesPageOutput::ImportSequence(file_path) => {
+                        let sequence = match custom::import(&file_path, self.preferences.base) {
+                            Ok(sequence) => sequence,
+                            Err(ImportError::InvalidDigits) => {
+                                return push_toast!(
+                                    format!(
+                                        "Failed to import the sequence, the file must only contain base {} digits",
+                                        self.preferences.base
+                                    ),
+                                    3,
+                                    sender
+                                );
+                            }
+                            Err(ImportError::NoFreeId) => {
+                                return push_toast!(
+                                    format!(
+                                        "Failed to import the sequence, there are already {} imported sequences",
+                                        custom::MAX_CUSTOM_SEQUENCES
+                                    ),
+                                    3,
+                                    sender
+                                );
+                            }
+                            Err(ImportError::Storage(err)) => {
+                                return report_error!("Failed to import the sequence", err, sender);
+                            }
+                        };
+                        push_toast!(format!("Imported \"{}\"", sequence.get().name()), 2, sender);
+                        let _ = self
+                            .preferences_page
+                            .sender()
+                            .send(PreferencesPageInput::AddSequence(sequence));
+                        self.preferences.sequence = sequence;
+                        self.load_sequence(&sender);
+                    }
+                    PreferencesPageOutput::SetQuizRange(quiz_range) => {
+                        self.preferences.quiz_range = quiz_range
+                    }
+                    PreferencesPageOutput::SetQuizLength(quiz_length) => {
+                        self.preferences.quiz_length = quiz_length
+                    }
+                    PreferencesPageOutput::SetQuizQuestions(quiz_questions) => {
+                        self.preferences.quiz_questions = quiz_questions
+                    }
+                    PreferencesPageOutput::SetQuizScoring(quiz_scoring) => {
+                        self.preferences.quiz_scoring = quiz_scoring
+                    }
+                    PreferencesPageOutput::SetClozeLength(cloze_length) => {
+                        self.preferences.cloze_length = cloze_length
+                    }
+                    PreferencesPageOutput::SetClozeDensity(cloze_density) => {
+                        self.preferences.cloze_density = cloze_density
+                    }
+                    PreferencesPageOutput::SetReverseLength(reverse_length) => {
+                        self.preferences.reverse_length = reverse_length
+                    }
+                    PreferencesPageOutput::SetChoiceLength(choice_length) => {
+                        self.preferences.choice_length = choice_length
+                    }
+                    PreferencesPageOutput::SetChoiceContext(choice_context) => {
+                        self.preferences.choice_context = choice_context
+                    }
                     PreferencesPageOutput::SetDigitsPerRow(digits_per_row) => {
                         self.preferences.digits_per_row = digits_per_row;
 
@@ -465,8 +1313,8 @@ impl SimpleComponent for AppModel {
                         }
                     }
                 };
-                if AppPreferences::set(self.preferences).is_err() {
-                    push_toast!("Failed to save preference", 2, sender);
+                if let Err(err) = AppPreferences::set(self.preferences) {
+                    report_error!("Failed to save the preferences", err, sender);
                 }
             }
             AppInput::PushToast((text, timeout)) => {
@@ -481,23 +1329,22 @@ impl SimpleComponent for AppModel {
             AppInput::SwitchPage(page) => {
                 self.current_page = page;
                 match self.current_page {
-                    AppPages::Placeholder => {
-                        self.reset_digits();
-                        self.clear_ticker();
-                    }
-                    AppPages::Memoriser if self.preferences.mode == AppMode::Learn => PI_DIGITS
-                        .iter()
-                        .take(config::PRELOADED_DIGITS)
-                        .for_each(|d| {
-                            self.pi_digits.guard().push_back((
-                                *d,
-                                PiDigitState::Placeholder,
-                                self.preferences.digits_per_row,
-                            ));
-                        }),
-                    _ => {}
+                    AppPages::Placeholder => self.reset_game(),
+                    AppPages::Memoriser => self.start_game(&sender),
+                    AppPages::Results => {}
                 }
             }
         };
     }
+
+    fn shutdown(&mut self, _widgets: &mut Self::Widgets, _output: relm4::Sender<Self::Output>) {
+        // the window is closed, the running game is resumed from the welcome page next time
+        if let Err(err) = self.save_game() {
+            gtk::glib::g_warning!(
+                config::LOG_DOMAIN,
+                "Failed to save the running game: {}",
+                err
+            );
+        }
+    }
 }
@@ -1,16 +1,52 @@
 use std::{
-    fs::{self, File},
-    path::{Path, PathBuf},
+    collections::{HashMap, VecDeque},
+    fs, io,
+    path::Path,
+    str::FromStr,
     time::Duration,
 };
 
-use super::AppMode;
+use super::storage::{back_up, get_profile_config_dir_path, StorageError};
+use crate::{
+    config,
+    digits::{base::BASES, Digits, Sequence, PI_DIGITS},
+    game::{quiz::QuizScoring, random, AppMode, GameSettings},
+};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct AppPreferences {
     pub mode: AppMode,
     pub timeout: Option<Duration>,
     pub digits_per_row: u8,
+    /// number of pi digits skipped at the start of a game
+    pub start_offset: usize,
+    /// number of digits in the drill window
+    pub drill_length: usize,
+    /// number of clean laps in a row needed to widen the drill window (0 to never widen)
+    pub drill_widen_after: u8,
+    /// number of digits to play with, computed past the embedded ones
+    pub digits_len: usize,
+    /// the constant whose digits are played
+    pub sequence: Sequence,
+    /// base the digits are played in
+    pub base: u8,
+    /// the quiz questions are picked in the first `quiz_range` digits
+    pub quiz_range: usize,
+    /// number of digits asked per quiz question
+    pub quiz_length: u8,
+    /// number of questions per quiz
+    pub quiz_questions: u8,
+    pub quiz_scoring: QuizScoring,
+    /// number of digits in the cloze window
+    pub cloze_length: usize,
+    /// percentage of the cloze window that is hidden
+    pub cloze_density: u8,
+    /// number of digits typed backwards in reverse mode
+    pub reverse_length: usize,
+    /// number of digits of the chunks to pick in choice mode
+    pub choice_length: u8,
+    /// number of digits shown before the chunk to pick in choice mode
+    pub choice_context: u8,
 }
 
 impl Default for AppPreferences {
@@ -19,53 +55,155 @@ impl Default for AppPreferences {
             mode: AppMode::Learn,
             timeout: None,
             digits_per_row: 10,
+            start_offset: 0,
+            drill_length: 20,
+            drill_widen_after: 3,
+            digits_len: PI_DIGITS.len(),
+            sequence: Sequence::Pi,
+            base: 10,
+            quiz_range: 100,
+            quiz_length: 10,
+            quiz_questions: 10,
+            quiz_scoring: QuizScoring::PerDigit,
+            cloze_length: 50,
+            cloze_density: 20,
+            reverse_length: 20,
+            choice_length: 4,
+            choice_context: config::CONTEXT_DIGITS as u8,
         }
     }
 }
 
-/// Simple macro to return default when error (can be seen as an enhance '?')
-macro_rules! tod {
-    ($expr:expr) => {
-        match $expr {
-            Ok(val) => val,
-            Err(_) => return Self::default(),
-        }
-    };
+const PREFERENCES_FILE_NAME: &str = "preferences.conf";
+/// bumped when the meaning of a key changes, adding a key does not need a new version
+const PREFERENCES_VERSION: u32 = 1;
+
+/// binary format used before the versioned one, it is migrated on the first launch
+const LEGACY_PREFERENCES_FILE_NAME: &str = "preferences";
+const LEGACY_PREFERENCES_BYTES_LEN: usize = 6;
+
+/// the value of `key` if it is there and parses
+fn value<T: FromStr>(values: &HashMap<&str, &str>, key: &str) -> Option<T> {
+    values.get(key)?.parse().ok()
 }
-const PREFERENCES_BYTES_LEN: usize = 6;
 
 impl AppPreferences {
-    /// return the path to the app's config file (and ensure that all the necessary directories and files exists)
-    fn get_config_file_path() -> Result<PathBuf, ()> {
-        let mut config_path = dirs::config_dir().ok_or(())?;
-
-        config_path.push("gnopi");
-        fs::create_dir_all(&config_path).map_err(|_| ())?;
+    /// one "key = value" line per preference, after the format version
+    pub fn to_text(self) -> String {
+        let fields = [
+            ("mode", (self.mode as u8).to_string()),
+            (
+                "timeout_ms",
+                self.timeout.unwrap_or_default().as_millis().to_string(),
+            ),
+            ("digits_per_row", self.digits_per_row.to_string()),
+            ("start_offset", self.start_offset.to_string()),
+            ("drill_length", self.drill_length.to_string()),
+            ("drill_widen_after", self.drill_widen_after.to_string()),
+            ("digits_len", self.digits_len.to_string()),
+            ("sequence", u8::from(self.sequence).to_string()),
+            ("base", self.base.to_string()),
+            ("quiz_range", self.quiz_range.to_string()),
+            ("quiz_length", self.quiz_length.to_string()),
+            ("quiz_questions", self.quiz_questions.to_string()),
+            ("quiz_scoring", (self.quiz_scoring as u8).to_string()),
+            ("cloze_length", self.cloze_length.to_string()),
+            ("cloze_density", self.cloze_density.to_string()),
+            ("reverse_length", self.reverse_length.to_string()),
+            ("choice_length", self.choice_length.to_string()),
+            ("choice_context", self.choice_context.to_string()),
+        ];
 
-        config_path.push("preferences");
-        if !Path::exists(&config_path) {
-            File::create(&config_path).map_err(|_| ())?;
+        let mut text = format!("# GnoPi preferences\nversion = {PREFERENCES_VERSION}\n");
+        for (key, value) in fields {
+            text += &format!("{key} = {value}\n");
         }
-
-        Ok(config_path)
+        text
     }
 
-    fn as_bytes(&self) -> [u8; PREFERENCES_BYTES_LEN] {
-        let mut bytes = [0; PREFERENCES_BYTES_LEN];
-        bytes[0] = self.mode as u8; // if less than 255 appmode it should be ok...
-        bytes[5] = self.digits_per_row;
-
-        let timeout_bytes = self.timeout.unwrap_or_default().as_secs_f32().to_be_bytes();
-        bytes[1..=4].copy_from_slice(&timeout_bytes);
+    pub fn from_text(text: &str) -> Result<Self, StorageError> {
+        let values = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect::<HashMap<_, _>>();
+        // a newer version may have changed the meaning of a key, it is not read
+        match value::<u32>(&values, "version") {
+            Some(version) if (1..=PREFERENCES_VERSION).contains(&version) => {}
+            Some(version) if version > PREFERENCES_VERSION => return Err(StorageError::Newer),
+            _ => return Err(StorageError::Corrupted),
+        }
 
-        bytes
+        // a missing or invalid value only resets its own preference
+        let default = Self::default();
+        Ok(AppPreferences {
+            mode: value::<u8>(&values, "mode")
+                .and_then(|mode| AppMode::try_from(mode).ok())
+                .unwrap_or(default.mode),
+            timeout: match value(&values, "timeout_ms") {
+                Some(0) => None,
+                Some(ms) => Some(Duration::from_millis(ms)),
+                None => default.timeout,
+            },
+            digits_per_row: value(&values, "digits_per_row")
+                .filter(|digits_per_row| *digits_per_row >= 5)
+                .unwrap_or(default.digits_per_row),
+            start_offset: value(&values, "start_offset")
+                .filter(|start_offset| *start_offset <= config::MAX_START_OFFSET)
+                .unwrap_or(default.start_offset),
+            drill_length: value(&values, "drill_length")
+                .filter(|drill_length| *drill_length > 0)
+                .unwrap_or(default.drill_length),
+            drill_widen_after: value(&values, "drill_widen_after")
+                .unwrap_or(default.drill_widen_after),
+            digits_len: value(&values, "digits_len")
+                .filter(|digits_len| (1..=config::MAX_DIGITS).contains(digits_len))
+                .unwrap_or(default.digits_len),
+            sequence: value::<u8>(&values, "sequence")
+                .and_then(|sequence| Sequence::try_from(sequence).ok())
+                .unwrap_or(default.sequence),
+            base: value(&values, "base")
+                .filter(|base| BASES.contains(base))
+                .unwrap_or(default.base),
+            quiz_range: value(&values, "quiz_range")
+                .filter(|quiz_range| *quiz_range > 0)
+                .unwrap_or(default.quiz_range),
+            quiz_length: value(&values, "quiz_length")
+                .filter(|quiz_length| *quiz_length > 0)
+                .unwrap_or(default.quiz_length),
+            quiz_questions: value(&values, "quiz_questions")
+                .filter(|quiz_questions| *quiz_questions > 0)
+                .unwrap_or(default.quiz_questions),
+            quiz_scoring: value::<u8>(&values, "quiz_scoring")
+                .and_then(|quiz_scoring| QuizScoring::try_from(quiz_scoring).ok())
+                .unwrap_or(default.quiz_scoring),
+            cloze_length: value(&values, "cloze_length")
+                .filter(|cloze_length| *cloze_length > 0)
+                .unwrap_or(default.cloze_length),
+            cloze_density: value(&values, "cloze_density")
+                .filter(|cloze_density| (1..=100).contains(cloze_density))
+                .unwrap_or(default.cloze_density),
+            reverse_length: value(&values, "reverse_length")
+                .filter(|reverse_length| *reverse_length > 0)
+                .unwrap_or(default.reverse_length),
+            choice_length: value(&values, "choice_length")
+                .filter(|choice_length| *choice_length > 0)
+                .unwrap_or(default.choice_length),
+            choice_context: value(&values, "choice_context").unwrap_or(default.choice_context),
+        })
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
-        if bytes.len() != PREFERENCES_BYTES_LEN {
+    /// the legacy file holds the mode, the timeout in seconds (big endian f32) and the digits per row
+    fn from_legacy_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        if bytes.len() != LEGACY_PREFERENCES_BYTES_LEN {
+            return Err(()); // data corrupted
+        }
+        // the legacy app only had the blind, learn and instant death modes
+        if bytes[0] > AppMode::InstantDeath as u8 {
             return Err(()); // data corrupted
         }
-
         let mode = AppMode::try_from(bytes[0])?;
         let digits_per_row = bytes[5];
         if digits_per_row < 5 {
@@ -85,17 +223,188 @@ impl AppPreferences {
             mode,
             timeout,
             digits_per_row,
+            ..Self::default()
         })
     }
 
-    pub fn load() -> Self {
-        let config_file_path = tod!(Self::get_config_file_path());
-        let bytes = tod!(fs::read(config_file_path));
-        tod!(Self::from_bytes(&bytes))
+    /// settings of a game over `digits` starting at `start_index`
+    pub fn game_settings(&self, digits: Digits, start_index: usize) -> GameSettings {
+        GameSettings {
+            mode: self.mode,
+            digits,
+            timeout: self.timeout,
+            start_index,
+            drill_length: self.drill_length,
+            drill_widen_after: self.drill_widen_after,
+            review_queue: VecDeque::new(),
+            quiz_range: self.quiz_range,
+            quiz_length: self.quiz_length as usize,
+            quiz_questions: self.quiz_questions as usize,
+            quiz_scoring: self.quiz_scoring,
+            cloze_length: self.cloze_length,
+            cloze_density: self.cloze_density,
+            reverse_length: self.reverse_length,
+            choice_length: self.choice_length as usize,
+            choice_context: self.choice_context as usize,
+            seed: random::time_seed(),
+        }
     }
 
-    pub fn set(new_pref: Self) -> Result<(), ()> {
-        let config_file_path = Self::get_config_file_path()?;
-        fs::write(config_file_path, new_pref.as_bytes()).map_err(|_| ())
+    /// the saved preferences, an unreadable file is backed up and the defaults are used in its place
+    pub fn load() -> Result<Self, StorageError> {
+        let config_dir_path = get_profile_config_dir_path()?;
+        let config_file_path = config_dir_path.join(PREFERENCES_FILE_NAME);
+        let bytes = match fs::read(&config_file_path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Self::migrate(&config_dir_path)
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let text = String::from_utf8_lossy(&bytes);
+        match Self::from_text(&text) {
+            Err(StorageError::Corrupted) => {
+                back_up(&config_file_path)?;
+                Err(StorageError::Corrupted)
+            }
+            // left as it is for the newer version
+            result => result,
+        }
+    }
+
+    /// read the preferences of the legacy binary file and move them to the versioned one, only done once
+    fn migrate(config_dir_path: &Path) -> Result<Self, StorageError> {
+        let legacy_file_path = config_dir_path.join(LEGACY_PREFERENCES_FILE_NAME);
+        let bytes = match fs::read(&legacy_file_path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        // the legacy app created the file empty, until a preference was changed
+        if bytes.is_empty() {
+            fs::remove_file(legacy_file_path)?;
+            return Ok(Self::default());
+        }
+
+        let preferences = match Self::from_legacy_bytes(&bytes) {
+            Ok(preferences) => preferences,
+            Err(_) => {
+                back_up(&legacy_file_path)?;
+                return Err(StorageError::Corrupted);
+            }
+        };
+        Self::set(preferences)?;
+        fs::remove_file(legacy_file_path)?;
+        Ok(preferences)
+    }
+
+    pub fn set(new_pref: Self) -> Result<(), StorageError> {
+        let config_file_path = get_profile_config_dir_path()?.join(PREFERENCES_FILE_NAME);
+        // the preferences of a newer version would be lost
+        if let Ok(text) = fs::read_to_string(&config_file_path) {
+            if let Err(StorageError::Newer) = Self::from_text(&text) {
+                return Err(StorageError::Newer);
+            }
+        }
+        fs::write(config_file_path, new_pref.to_text())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn customized() -> AppPreferences {
+        AppPreferences {
+            mode: AppMode::Cloze,
+            timeout: Some(Duration::from_millis(2500)),
+            digits_per_row: 12,
+            start_offset: 100,
+            drill_length: 30,
+            drill_widen_after: 0,
+            digits_len: 5000,
+            sequence: Sequence::E,
+            base: 16,
+            quiz_range: 1000,
+            quiz_length: 5,
+            quiz_questions: 20,
+            quiz_scoring: QuizScoring::PerQuestion,
+            cloze_length: 80,
+            cloze_density: 50,
+            reverse_length: 10,
+            choice_length: 6,
+            choice_context: 0,
+        }
+    }
+
+    #[test]
+    fn text_round_trips() {
+        for preferences in [AppPreferences::default(), customized()] {
+            assert_eq!(
+                AppPreferences::from_text(&preferences.to_text()).unwrap(),
+                preferences
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_missing_and_invalid_keys_do_not_reset_the_others() {
+        let text = "version = 1\nmode = 3\nbase = 7\nnew_preference = yes\ndigits_per_row=8";
+        let preferences = AppPreferences::from_text(text).unwrap();
+        assert_eq!(preferences.mode, AppMode::Drill);
+        assert_eq!(preferences.digits_per_row, 8);
+        assert_eq!(preferences.base, AppPreferences::default().base);
+        assert_eq!(preferences.quiz_range, AppPreferences::default().quiz_range);
+
+        assert!(matches!(
+            AppPreferences::from_text("mode = 3"),
+            Err(StorageError::Corrupted)
+        ));
+        let newer = format!("version = {}\nmode = 3", PREFERENCES_VERSION + 1);
+        assert!(matches!(
+            AppPreferences::from_text(&newer),
+            Err(StorageError::Newer)
+        ));
+    }
+
+    #[test]
+    fn legacy_six_bytes_are_migrated() {
+        let mut bytes = vec![AppMode::Blind as u8];
+        bytes.extend(1.5f32.to_be_bytes());
+        bytes.push(15);
+
+        let preferences = AppPreferences::from_legacy_bytes(&bytes).unwrap();
+        assert_eq!(preferences.mode, AppMode::Blind);
+        assert_eq!(preferences.timeout, Some(Duration::from_secs_f32(1.5)));
+        assert_eq!(preferences.digits_per_row, 15);
+        assert_eq!(
+            AppPreferences {
+                mode: AppPreferences::default().mode,
+                timeout: None,
+                digits_per_row: AppPreferences::default().digits_per_row,
+                ..preferences
+            },
+            AppPreferences::default()
+        );
+        assert_eq!(AppPreferences::from_legacy_bytes(&bytes[..5]), Err(()));
+        bytes.extend(0u32.to_be_bytes());
+        assert_eq!(AppPreferences::from_legacy_bytes(&bytes), Err(()));
+    }
+
+    #[test]
+    fn legacy_modes_stop_at_instant_death() {
+        let mut bytes = vec![AppMode::InstantDeath as u8];
+        bytes.extend(0f32.to_be_bytes());
+        bytes.push(10);
+        assert_eq!(
+            AppPreferences::from_legacy_bytes(&bytes).map(|p| p.mode),
+            Ok(AppMode::InstantDeath)
+        );
+
+        // the drill mode came with the versioned file
+        bytes[0] = AppMode::Drill as u8;
+        assert_eq!(AppPreferences::from_legacy_bytes(&bytes), Err(()));
     }
 }
@@ -0,0 +1,155 @@
+use std::{fs, io, time::Duration};
+
+use super::storage::{get_stats_file_path, StorageError};
+use crate::{
+    digits::{Digits, Sequence},
+    game::{quiz::QuizScoring, AppMode, GameInput, GameSettings, SavedGame},
+};
+
+const SAVED_GAME_FILE_NAME: &str = "saved_game";
+
+/// one token per input: "d<digit>", "c<candidate>" or "b" for a backspace
+fn input_to_token(input: &GameInput) -> Option<String> {
+    match input {
+        GameInput::Digit(digit) => Some(format!("d{digit}")),
+        GameInput::Choose(candidate) => Some(format!("c{candidate}")),
+        GameInput::Backspace => Some("b".to_string()),
+        _ => None,
+    }
+}
+
+fn input_from_token(token: &str) -> Result<GameInput, ()> {
+    if token == "b" {
+        return Ok(GameInput::Backspace);
+    }
+    if let Some(digit) = token.strip_prefix('d') {
+        return Ok(GameInput::Digit(digit.parse().map_err(|_| ())?));
+    }
+    let candidate = token.strip_prefix('c').ok_or(())?;
+    Ok(GameInput::Choose(candidate.parse().map_err(|_| ())?))
+}
+
+/// three lines: the settings and the time played, the review queue, the inputs
+fn to_lines(saved: &SavedGame) -> String {
+    let settings = &saved.settings;
+    let review_queue = settings
+        .review_queue
+        .iter()
+        .map(|chunk| chunk.to_string())
+        .collect::<Vec<_>>();
+    let inputs = saved
+        .inputs
+        .iter()
+        .filter_map(input_to_token)
+        .collect::<Vec<_>>();
+
+    format!(
+        "{} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}\n{}\n{}\n",
+        settings.mode as u8,
+        settings.timeout.unwrap_or_default().as_millis(),
+        settings.start_index,
+        settings.drill_length,
+        settings.drill_widen_after,
+        settings.quiz_range,
+        settings.quiz_length,
+        settings.quiz_questions,
+        settings.quiz_scoring as u8,
+        settings.cloze_length,
+        settings.cloze_density,
+        settings.reverse_length,
+        settings.choice_length,
+        settings.choice_context,
+        settings.seed,
+        saved.elapsed.as_millis(),
+        review_queue.join(" "),
+        inputs.join(" ")
+    )
+}
+
+fn from_lines(content: &str, digits: Digits) -> Result<SavedGame, ()> {
+    let mut lines = content.lines();
+    let mut next_line = || lines.next().ok_or(());
+
+    let fields = next_line()?
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().map_err(|_| ()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if fields.len() != 16 {
+        return Err(()); // data corrupted
+    }
+    let byte = |field: u64| u8::try_from(field).map_err(|_| ());
+
+    let review_queue = next_line()?
+        .split_whitespace()
+        .map(|chunk| chunk.parse::<usize>().map_err(|_| ()))
+        .collect::<Result<_, _>>()?;
+    let inputs = next_line()?
+        .split_whitespace()
+        .map(input_from_token)
+        .collect::<Result<_, _>>()?;
+
+    let settings = GameSettings {
+        mode: AppMode::try_from(byte(fields[0])?)?,
+        digits,
+        timeout: match fields[1] {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        },
+        start_index: fields[2] as usize,
+        drill_length: fields[3] as usize,
+        drill_widen_after: byte(fields[4])?,
+        review_queue,
+        quiz_range: fields[5] as usize,
+        quiz_length: fields[6] as usize,
+        quiz_questions: fields[7] as usize,
+        quiz_scoring: QuizScoring::try_from(byte(fields[8])?)?,
+        cloze_length: fields[9] as usize,
+        cloze_density: byte(fields[10])?,
+        reverse_length: fields[11] as usize,
+        choice_length: fields[12] as usize,
+        choice_context: fields[13] as usize,
+        seed: fields[14],
+    };
+    if settings.drill_length == 0
+        || settings.quiz_range == 0
+        || settings.quiz_length == 0
+        || settings.quiz_questions == 0
+        || settings.cloze_length == 0
+        || !(1..=100).contains(&settings.cloze_density)
+        || settings.reverse_length == 0
+        || settings.choice_length == 0
+    {
+        return Err(()); // data corrupted
+    }
+
+    Ok(SavedGame {
+        settings,
+        inputs,
+        elapsed: Duration::from_millis(fields[15]),
+    })
+}
+
+impl SavedGame {
+    /// the game left running with this sequence and base, played over `digits`
+    pub fn load(sequence: Sequence, base: u8, digits: Digits) -> Option<Self> {
+        let saved_game_file_path =
+            get_stats_file_path(sequence, base, SAVED_GAME_FILE_NAME).ok()?;
+        let content = fs::read_to_string(saved_game_file_path).ok()?;
+        from_lines(&content, digits).ok()
+    }
+
+    /// there is at most one saved game per sequence and base, the previous one is replaced
+    pub fn save(&self, sequence: Sequence, base: u8) -> Result<(), StorageError> {
+        let saved_game_file_path = get_stats_file_path(sequence, base, SAVED_GAME_FILE_NAME)?;
+        fs::write(saved_game_file_path, to_lines(self))?;
+        Ok(())
+    }
+
+    pub fn delete(sequence: Sequence, base: u8) -> Result<(), StorageError> {
+        let saved_game_file_path = get_stats_file_path(sequence, base, SAVED_GAME_FILE_NAME)?;
+        match fs::remove_file(saved_game_file_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::storage::{get_stats_file_path, StorageError};
+use crate::{
+    config,
+    digits::Sequence,
+    game::srs::{ChunkCard, Scheduler},
+};
+
+pub const SRS_FILE_NAME: &str = "srs";
+
+/// in days since the unix epoch
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86400
+}
+
+/// one line per card: "chunk ease interval repetitions due"
+fn to_line(card: &ChunkCard) -> String {
+    format!(
+        "{} {} {} {} {}",
+        card.chunk, card.ease, card.interval, card.repetitions, card.due
+    )
+}
+
+fn from_line(line: &str) -> Result<ChunkCard, ()> {
+    let mut fields = line.split_whitespace();
+    let mut next_field = || fields.next().ok_or(());
+
+    let chunk = next_field()?.parse::<usize>().map_err(|_| ())?;
+    let ease = next_field()?.parse::<f64>().map_err(|_| ())?;
+    let interval = next_field()?.parse::<u32>().map_err(|_| ())?;
+    let repetitions = next_field()?.parse::<u32>().map_err(|_| ())?;
+    let due = next_field()?.parse::<u64>().map_err(|_| ())?;
+    if ease < 1.3 || chunk >= config::SRS_CHUNKS_LEN {
+        return Err(()); // data corrupted
+    }
+
+    Ok(ChunkCard {
+        chunk,
+        ease,
+        interval,
+        repetitions,
+        due,
+    })
+}
+
+impl Scheduler {
+    pub fn load(sequence: Sequence, base: u8) -> Self {
+        let srs_file_path = match get_stats_file_path(sequence, base, SRS_FILE_NAME) {
+            Ok(path) => path,
+            Err(_) => return Self::default(),
+        };
+        let content = fs::read_to_string(srs_file_path).unwrap_or_default();
+
+        Self::new(content.lines().filter_map(|line| from_line(line).ok()))
+    }
+
+    /// add the cards of another schedule file, the most practised card of a chunk is kept
+    pub fn merge(&mut self, content: &str) {
+        let mut cards = self
+            .cards()
+            .map(|card| (card.chunk, *card))
+            .collect::<BTreeMap<_, _>>();
+        for other in content.lines().filter_map(|line| from_line(line).ok()) {
+            let card = cards.entry(other.chunk).or_insert(other);
+            if (other.repetitions, other.due) > (card.repetitions, card.due) {
+                *card = other;
+            }
+        }
+        *self = Self::new(cards.into_values());
+    }
+
+    /// rewrite the whole schedule, there is at most one line per chunk
+    pub fn save(&self, sequence: Sequence, base: u8) -> Result<(), StorageError> {
+        let srs_file_path = get_stats_file_path(sequence, base, SRS_FILE_NAME)?;
+        let content = self
+            .cards()
+            .map(|card| to_line(card) + "\n")
+            .collect::<String>();
+        fs::write(srs_file_path, content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{PoisonError, RwLock},
+};
+
+use crate::digits::Sequence;
+
+/// the profile whose data existed before the profiles, it keeps them where they were
+pub const DEFAULT_PROFILE: &str = "Default";
+const PROFILES_DIR_NAME: &str = "profiles";
+/// remembers the profile used last, in the app's config directory
+const ACTIVE_PROFILE_FILE_NAME: &str = "profile";
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// the profile in use, `None` for the default one
+static ACTIVE_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Why the user data could not be read or written
+#[derive(Debug)]
+pub enum StorageError {
+    /// the system gives no config or data directory for the user
+    MissingDir,
+    Io(io::Error),
+    /// the file is there but its content makes no sense
+    Corrupted,
+    /// the file was written by a newer version of the app, it can not be read nor replaced
+    Newer,
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::MissingDir => write!(f, "no directory to store the user data"),
+            StorageError::Io(err) => write!(f, "{err}"),
+            StorageError::Corrupted => write!(f, "the file is corrupted"),
+            StorageError::Newer => write!(f, "the file was written by a newer version of the app"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+/// move an unreadable file out of the way, it is kept next to it as "<file name>.bak"
+pub fn back_up(file_path: &Path) -> Result<PathBuf, StorageError> {
+    let mut backup_file_name = file_path.file_name().unwrap_or_default().to_owned();
+    backup_file_name.push(".bak");
+    let backup_path = file_path.with_file_name(backup_file_name);
+
+    fs::rename(file_path, &backup_path)?;
+    Ok(backup_path)
+}
+
+/// return the path to the app's config directory (and ensure that it exists)
+pub fn get_config_dir_path() -> Result<PathBuf, StorageError> {
+    let mut config_path = dirs::config_dir().ok_or(StorageError::MissingDir)?;
+
+    config_path.push("gnopi");
+    fs::create_dir_all(&config_path)?;
+    Ok(config_path)
+}
+
+/// return the path to the app's data directory (and ensure that it exists)
+pub fn get_data_dir_path() -> Result<PathBuf, StorageError> {
+    let mut data_path = dirs::data_dir().ok_or(StorageError::MissingDir)?;
+
+    data_path.push("gnopi");
+    fs::create_dir_all(&data_path)?;
+    Ok(data_path)
+}
+
+/// return the path to a file of the sequence's data directory (and ensure that all the necessary directories exists)
+pub fn get_data_file_path(sequence: Sequence, file_name: &str) -> Result<PathBuf, StorageError> {
+    let mut data_path = get_data_dir_path()?;
+
+    if let Some(sequence_dir) = sequence.data_dir() {
+        data_path.push(sequence_dir);
+        fs::create_dir_all(&data_path)?;
+    }
+
+    data_path.push(file_name);
+    Ok(data_path)
+}
+
+/// the history and stats of the sequence are kept apart for each profile and base
+fn get_stats_dir_path(sequence: Sequence, base: u8) -> Result<PathBuf, StorageError> {
+    let mut stats_path = get_profile_data_dir_path()?;
+
+    if let Some(sequence_dir) = sequence.data_dir() {
+        stats_path.push(sequence_dir);
+    }
+    // decimal stats stay where they were before the other bases existed
+    if base != 10 {
+        stats_path.push(format!("base{base}"));
+    }
+    Ok(stats_path)
+}
+
+/// return the path to a file of the history and stats (and ensure that all the necessary directories exists)
+pub fn get_stats_file_path(
+    sequence: Sequence,
+    base: u8,
+    file_name: &str,
+) -> Result<PathBuf, StorageError> {
+    let mut stats_path = get_stats_dir_path(sequence, base)?;
+    fs::create_dir_all(&stats_path)?;
+
+    stats_path.push(file_name);
+    Ok(stats_path)
+}
+
+/// the content of a file of the history and stats, without creating any directory
+pub fn read_stats_file(
+    sequence: Sequence,
+    base: u8,
+    file_name: &str,
+) -> Result<Vec<u8>, StorageError> {
+    let stats_file_path = get_stats_dir_path(sequence, base)?.join(file_name);
+    Ok(fs::read(stats_file_path)?)
+}
+
+fn custom_profile() -> Option<String> {
+    ACTIVE_PROFILE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// name of the profile in use
+pub fn active_profile() -> String {
+    custom_profile().unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// return the path to the directory of a profile other than the default one
+fn get_profile_dir_path(profile: &str) -> Result<PathBuf, StorageError> {
+    Ok(get_data_dir_path()?.join(PROFILES_DIR_NAME).join(profile))
+}
+
+/// return the path to the active profile's data directory (and ensure that it exists)
+pub fn get_profile_data_dir_path() -> Result<PathBuf, StorageError> {
+    match custom_profile() {
+        Some(profile) => {
+            let profile_path = get_profile_dir_path(&profile)?;
+            fs::create_dir_all(&profile_path)?;
+            Ok(profile_path)
+        }
+        None => get_data_dir_path(),
+    }
+}
+
+/// return the path to the directory of the active profile's preferences (and ensure that it exists)
+pub fn get_profile_config_dir_path() -> Result<PathBuf, StorageError> {
+    match custom_profile() {
+        Some(_) => get_profile_data_dir_path(),
+        None => get_config_dir_path(),
+    }
+}
+
+/// letters, digits, spaces, dashes and underscores, so that the name is a valid directory name
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.trim() == name
+        && name.chars().count() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+        && !name.eq_ignore_ascii_case(DEFAULT_PROFILE)
+}
+
+/// every profile, the default one first then the others by name
+pub fn list_profiles() -> Vec<String> {
+    let mut profiles = get_data_dir_path()
+        .and_then(|path| Ok(fs::read_dir(path.join(PROFILES_DIR_NAME))?))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_valid_profile_name(name))
+        .collect::<Vec<_>>();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+pub fn create_profile(name: &str) -> Result<(), StorageError> {
+    if !is_valid_profile_name(name) {
+        return Err(io::Error::from(io::ErrorKind::InvalidInput).into());
+    }
+    let profile_path = get_profile_dir_path(name)?;
+    fs::create_dir_all(profile_path.parent().unwrap_or(&profile_path))?;
+    // fails if the profile already exists
+    fs::create_dir(profile_path)?;
+    Ok(())
+}
+
+/// use the data of the profile from now on, and on the next launches
+pub fn switch_profile(name: &str) -> Result<(), StorageError> {
+    let profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+    if let Some(profile) = &profile {
+        if !get_profile_dir_path(profile)?.is_dir() {
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+    }
+
+    fs::write(get_config_dir_path()?.join(ACTIVE_PROFILE_FILE_NAME), name)?;
+    *ACTIVE_PROFILE
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = profile;
+    Ok(())
+}
+
+/// go back to the profile used last time, the default one if it was removed
+pub fn load_active_profile() {
+    let name = get_config_dir_path()
+        .and_then(|path| Ok(fs::read_to_string(path.join(ACTIVE_PROFILE_FILE_NAME))?))
+        .unwrap_or_default();
+    let name = name.trim();
+    if is_valid_profile_name(name) && get_profile_dir_path(name).is_ok_and(|path| path.is_dir()) {
+        *ACTIVE_PROFILE
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(name.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_directory_names() {
+        for name in ["Alice", "team-2", "Bob Smith", "élève_1"] {
+            assert!(is_valid_profile_name(name), "{name}");
+        }
+        for name in [
+            "",
+            " Alice",
+            "../Alice",
+            "a/b",
+            ".hidden",
+            "default",
+            &"a".repeat(33),
+        ] {
+            assert!(!is_valid_profile_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn back_up_keeps_the_file_aside() {
+        let dir = std::env::temp_dir().join(format!("gnopi-back-up-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("preferences.conf");
+        fs::write(&file_path, "garbage").unwrap();
+
+        let backup_path = back_up(&file_path).unwrap();
+        assert_eq!(backup_path, dir.join("preferences.conf.bak"));
+        assert!(!file_path.exists());
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "garbage");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use super::{
+    archive::{self, Entry},
+    history::{History, HISTORY_FILE_NAME},
+    mistakes::{MistakeLog, MISTAKES_FILE_NAME},
+    preferences::AppPreferences,
+    srs::SRS_FILE_NAME,
+    storage::{read_stats_file, StorageError},
+};
+use crate::{
+    digits::{
+        base::BASES,
+        custom::{self, ImportError},
+        Sequence,
+    },
+    game::srs::Scheduler,
+};
+
+/// tells the archives of the app apart, holds the version of their layout
+const VERSION_ENTRY: &str = "gnopi-data";
+const ARCHIVE_VERSION: u32 = 1;
+const PREFERENCES_ENTRY: &str = "preferences.conf";
+const STATS_FILE_NAMES: [&str; 3] = [HISTORY_FILE_NAME, MISTAKES_FILE_NAME, SRS_FILE_NAME];
+const CUSTOM_NAME_ENTRY: &str = "name";
+const CUSTOM_BASE_ENTRY: &str = "base";
+const CUSTOM_DIGITS_ENTRY: &str = "digits";
+
+/// What an import added to the active profile
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ImportSummary {
+    pub games: usize,
+    /// the same mistake made on both computers is only kept as often as on the one where it is the most frequent
+    pub mistakes: usize,
+}
+
+/// e.g. "stats/0/10/history" for the decimal history of π
+fn stats_entry_name(sequence: Sequence, base: u8, file_name: &str) -> String {
+    format!("stats/{}/{base}/{file_name}", u8::from(sequence))
+}
+
+fn parse_stats_entry_name(name: &str) -> Option<(Sequence, u8, &str)> {
+    let mut parts = name.strip_prefix("stats/")?.splitn(3, '/');
+    let sequence = Sequence::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let base = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|base| BASES.contains(base))?;
+    let file_name = parts
+        .next()
+        .filter(|name| STATS_FILE_NAMES.contains(name))?;
+    Some((sequence, base, file_name))
+}
+
+/// e.g. "custom/3/digits", the files of an imported sequence
+fn custom_entries(id: u8, name: &str, base: u8, digits: &[u8]) -> [Entry; 3] {
+    let entry = |file_name: &str, content: Vec<u8>| Entry {
+        name: format!("custom/{id}/{file_name}"),
+        content,
+    };
+    [
+        entry(CUSTOM_NAME_ENTRY, name.as_bytes().to_vec()),
+        entry(CUSTOM_BASE_ENTRY, base.to_string().into_bytes()),
+        entry(CUSTOM_DIGITS_ENTRY, digits.to_vec()),
+    ]
+}
+
+/// the name, base and digits of the imported sequences of an archive, by their exported id
+fn parse_custom_entries(entries: &[Entry]) -> BTreeMap<u8, (String, u8, Vec<u8>)> {
+    let mut files = BTreeMap::<u8, BTreeMap<&str, &[u8]>>::new();
+    for entry in entries {
+        let Some((id, file_name)) = entry
+            .name
+            .strip_prefix("custom/")
+            .and_then(|name| name.split_once('/'))
+        else {
+            continue;
+        };
+        let Ok(id) = id.parse() else {
+            continue;
+        };
+        files
+            .entry(id)
+            .or_default()
+            .insert(file_name, &entry.content);
+    }
+
+    let parse = |files: &BTreeMap<&str, &[u8]>| {
+        let name = std::str::from_utf8(files.get(CUSTOM_NAME_ENTRY)?).ok()?;
+        let base = std::str::from_utf8(files.get(CUSTOM_BASE_ENTRY)?)
+            .ok()?
+            .trim()
+            .parse::<u8>()
+            .ok()
+            .filter(|base| BASES.contains(base))?;
+        let digits = files.get(CUSTOM_DIGITS_ENTRY)?.to_vec();
+        Some((name.trim().to_string(), base, digits))
+    };
+    files
+        .iter()
+        .filter_map(|(id, files)| Some((*id, parse(files)?)))
+        .collect()
+}
+
+/// the local sequence of an exported one, None if it was not imported
+fn remap(sequence: Sequence, custom_ids: &BTreeMap<u8, Sequence>) -> Option<Sequence> {
+    match sequence {
+        Sequence::Custom(id) => custom_ids.get(&id).copied(),
+        sequence => Some(sequence),
+    }
+}
+
+/// bundle the preferences and the stats of the active profile in a tarball
+pub fn export(archive_path: &Path, preferences: AppPreferences) -> Result<(), StorageError> {
+    let mut entries = vec![
+        Entry {
+            name: VERSION_ENTRY.to_string(),
+            content: ARCHIVE_VERSION.to_string().into_bytes(),
+        },
+        Entry {
+            name: PREFERENCES_ENTRY.to_string(),
+            content: preferences.to_text().into_bytes(),
+        },
+    ];
+
+    // the ids of the imported sequences can mean other sequences on another computer,
+    // so they go along with their stats and get new ids on import
+    for sequence in Sequence::ALL.into_iter().chain(custom::list()) {
+        if let Sequence::Custom(id) = sequence {
+            let (name, base, digits) = custom::read(id)?;
+            entries.extend(custom_entries(id, &name, base, &digits));
+        }
+        for base in BASES {
+            for file_name in STATS_FILE_NAMES {
+                match read_stats_file(sequence, base, file_name) {
+                    Ok(content) => entries.push(Entry {
+                        name: stats_entry_name(sequence, base, file_name),
+                        content,
+                    }),
+                    Err(StorageError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+    }
+
+    fs::write(archive_path, archive::write_tar(&entries))?;
+    Ok(())
+}
+
+/// merge an exported tarball into the active profile, its preferences replace the current ones
+pub fn import(archive_path: &Path) -> Result<ImportSummary, StorageError> {
+    let entries = archive::read_tar(&fs::read(archive_path)?)?;
+    // a newer archive only adds entries, the known ones can still be read
+    let version = entries
+        .iter()
+        .find(|entry| entry.name == VERSION_ENTRY)
+        .and_then(|entry| std::str::from_utf8(&entry.content).ok())
+        .and_then(|version| version.trim().parse::<u32>().ok());
+    if version.unwrap_or(0) == 0 {
+        return Err(StorageError::Corrupted); // not an archive of the app
+    }
+
+    // an already imported sequence is reused, importing twice adds nothing
+    let mut custom_ids = BTreeMap::new();
+    for (id, (name, base, digits)) in parse_custom_entries(&entries) {
+        match custom::find_or_create(&name, base, &digits) {
+            Ok(sequence) => {
+                custom_ids.insert(id, sequence);
+            }
+            Err(ImportError::Storage(err)) => return Err(err),
+            Err(_) => {} // no room left or invalid digits, its stats are left out
+        }
+    }
+
+    let mut summary = ImportSummary::default();
+    for entry in &entries {
+        let content = String::from_utf8_lossy(&entry.content);
+        if entry.name == PREFERENCES_ENTRY {
+            let mut preferences = AppPreferences::from_text(&content)?;
+            preferences.sequence = remap(preferences.sequence, &custom_ids).unwrap_or(Sequence::Pi);
+            AppPreferences::set(preferences)?;
+            continue;
+        }
+
+        let Some((sequence, base, file_name)) = parse_stats_entry_name(&entry.name) else {
+            continue; // left by a newer version
+        };
+        let Some(sequence) = remap(sequence, &custom_ids) else {
+            continue; // its sequence could not be imported
+        };
+        match file_name {
+            HISTORY_FILE_NAME => summary.games += History::load(sequence, base).merge(&content)?,
+            MISTAKES_FILE_NAME => {
+                summary.mistakes += MistakeLog::load(sequence, base).merge(&content)?
+            }
+            SRS_FILE_NAME => {
+                let mut scheduler = Scheduler::load(sequence, base);
+                scheduler.merge(&content);
+                scheduler.save(sequence, base)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_entry_names_round_trip() {
+        for sequence in Sequence::ALL {
+            for base in BASES {
+                for file_name in STATS_FILE_NAMES {
+                    let name = stats_entry_name(sequence, base, file_name);
+                    assert_eq!(
+                        parse_stats_entry_name(&name),
+                        Some((sequence, base, file_name))
+                    );
+                }
+            }
+        }
+        assert_eq!(parse_stats_entry_name("stats/0/7/history"), None);
+        assert_eq!(parse_stats_entry_name("stats/0/10/saved_game"), None);
+        assert_eq!(parse_stats_entry_name("preferences.conf"), None);
+    }
+
+    #[test]
+    fn custom_sequences_round_trip() {
+        let custom = Sequence::Custom(3);
+        let mut entries = custom_entries(3, "Phone numbers", 16, &[0, 6, 15, 1]).to_vec();
+        entries.push(Entry {
+            name: stats_entry_name(custom, 16, HISTORY_FILE_NAME),
+            content: vec![],
+        });
+        let entries = archive::read_tar(&archive::write_tar(&entries)).unwrap();
+
+        let sequences = parse_custom_entries(&entries);
+        assert_eq!(
+            sequences.into_iter().collect::<Vec<_>>(),
+            vec![(3, ("Phone numbers".to_string(), 16, vec![0, 6, 15, 1]))]
+        );
+        assert_eq!(
+            parse_stats_entry_name(&entries[3].name),
+            Some((custom, 16, HISTORY_FILE_NAME))
+        );
+
+        // imported on another computer as its first sequence
+        let custom_ids = BTreeMap::from([(3, Sequence::Custom(0))]);
+        assert_eq!(remap(custom, &custom_ids), Some(Sequence::Custom(0)));
+        assert_eq!(remap(Sequence::Custom(4), &custom_ids), None);
+        assert_eq!(remap(Sequence::E, &custom_ids), Some(Sequence::E));
+    }
+
+    #[test]
+    fn incomplete_custom_sequences_are_skipped() {
+        let [name, base, digits] = custom_entries(0, "Primes", 10, &[2, 3, 5, 7]);
+        assert!(parse_custom_entries(&[name, digits]).is_empty());
+        let bad_base = Entry {
+            name: base.name,
+            content: b"7".to_vec(),
+        };
+        let [name, _, digits] = custom_entries(0, "Primes", 10, &[2, 3, 5, 7]);
+        assert!(parse_custom_entries(&[name, bad_base, digits]).is_empty());
+    }
+}
@@ -8,11 +8,23 @@ pub struct HeaderModel;
 #[derive(Debug)]
 pub enum HeaderOutput {
     Preferences,
+    Profiles,
+    Statistics,
+    Mistakes,
+    Search,
+    Export,
+    Import,
     About,
 }
 
 relm4::new_action_group!(HeaderMenuActionGroup, "win");
 relm4::new_stateless_action!(OpenPreference, HeaderMenuActionGroup, "preferences");
+relm4::new_stateless_action!(OpenProfiles, HeaderMenuActionGroup, "profiles");
+relm4::new_stateless_action!(OpenStatistics, HeaderMenuActionGroup, "statistics");
+relm4::new_stateless_action!(OpenMistakes, HeaderMenuActionGroup, "mistakes");
+relm4::new_stateless_action!(OpenSearch, HeaderMenuActionGroup, "search");
+relm4::new_stateless_action!(OpenExport, HeaderMenuActionGroup, "export");
+relm4::new_stateless_action!(OpenImport, HeaderMenuActionGroup, "import");
 relm4::new_stateless_action!(OpenAbout, HeaderMenuActionGroup, "about");
 
 #[relm4::component(pub)]
@@ -35,6 +47,12 @@ impl SimpleComponent for HeaderModel {
     menu! {
         main_menu: {
             "Preferences" => OpenPreference,
+            "Profiles" => OpenProfiles,
+            "Statistics" => OpenStatistics,
+            "Mistakes" => OpenMistakes,
+            "Find a number" => OpenSearch,
+            "Export data…" => OpenExport,
+            "Import data…" => OpenImport,
             "About GnoPi" => OpenAbout,
         }
     }
@@ -56,6 +74,60 @@ impl SimpleComponent for HeaderModel {
             })
         };
 
+        let senderu = sender.clone();
+        let action_profiles: RelmAction<OpenProfiles> = {
+            RelmAction::new_stateless(move |_| {
+                senderu
+                    .output(HeaderOutput::Profiles)
+                    .expect("Failed to open profiles");
+            })
+        };
+
+        let senders = sender.clone();
+        let action_statistics: RelmAction<OpenStatistics> = {
+            RelmAction::new_stateless(move |_| {
+                senders
+                    .output(HeaderOutput::Statistics)
+                    .expect("Failed to open statistics");
+            })
+        };
+
+        let senderm = sender.clone();
+        let action_mistakes: RelmAction<OpenMistakes> = {
+            RelmAction::new_stateless(move |_| {
+                senderm
+                    .output(HeaderOutput::Mistakes)
+                    .expect("Failed to open mistakes");
+            })
+        };
+
+        let senderf = sender.clone();
+        let action_search: RelmAction<OpenSearch> = {
+            RelmAction::new_stateless(move |_| {
+                senderf
+                    .output(HeaderOutput::Search)
+                    .expect("Failed to open search");
+            })
+        };
+
+        let sendere = sender.clone();
+        let action_export: RelmAction<OpenExport> = {
+            RelmAction::new_stateless(move |_| {
+                sendere
+                    .output(HeaderOutput::Export)
+                    .expect("Failed to open export");
+            })
+        };
+
+        let senderi = sender.clone();
+        let action_import: RelmAction<OpenImport> = {
+            RelmAction::new_stateless(move |_| {
+                senderi
+                    .output(HeaderOutput::Import)
+                    .expect("Failed to open import");
+            })
+        };
+
         let sendera = sender.clone();
         let action_about: RelmAction<OpenAbout> = {
             RelmAction::new_stateless(move |_| {
@@ -67,6 +139,12 @@ impl SimpleComponent for HeaderModel {
 
         let mut group = RelmActionGroup::<HeaderMenuActionGroup>::new();
         group.add_action(action_preference);
+        group.add_action(action_profiles);
+        group.add_action(action_statistics);
+        group.add_action(action_mistakes);
+        group.add_action(action_search);
+        group.add_action(action_export);
+        group.add_action(action_import);
         group.add_action(action_about);
         group.register_for_widget(&widgets.header);
 
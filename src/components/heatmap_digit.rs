@@ -0,0 +1,67 @@
+use adw::prelude::*;
+use relm4::{
+    adw,
+    factory::{positions::GridPosition, Position},
+    gtk,
+    prelude::{DynamicIndex, FactoryComponent},
+    FactorySender,
+};
+
+use super::pi_digit::digit_grid_position;
+use crate::digits::digit_char;
+
+/// pango markup of the text with a red background proportional to value/max
+pub fn heat_markup(text: &str, value: u32, max: u32) -> String {
+    if value == 0 || max == 0 {
+        return text.to_string();
+    }
+    let heat = (100 * value / max).max(1);
+    format!("<span background=\"#e01b24\" bgalpha=\"{heat}%\"> {text} </span>")
+}
+
+/// A digit of the heatmap, the more errors the redder
+pub struct HeatmapDigitModel {
+    pub digit: u8,
+    pub errors: u32,
+    pub max_errors: u32,
+    pub digits_per_row: u8,
+}
+
+impl Position<GridPosition, DynamicIndex> for HeatmapDigitModel {
+    fn position(&self, index: &DynamicIndex) -> GridPosition {
+        digit_grid_position(index.current_index(), self.digits_per_row)
+    }
+}
+
+#[relm4::factory(pub)]
+impl FactoryComponent for HeatmapDigitModel {
+    type ParentWidget = gtk::Grid;
+    type Input = ();
+    type Output = ();
+    /// (digit, errors, max_errors, digits_per_row)
+    type Init = (u8, u32, u32, u8);
+    type CommandOutput = ();
+
+    view! {
+        #[root]
+        gtk::Label {
+            set_css_classes: &["title-3", "monospace"],
+            set_use_markup: true,
+            set_markup: &heat_markup(&digit_char(self.digit).to_string(), self.errors, self.max_errors),
+            set_tooltip_text: Some(&format!("{} error(s)", self.errors)),
+        }
+    }
+
+    fn init_model(
+        (digit, errors, max_errors, digits_per_row): Self::Init,
+        _index: &DynamicIndex,
+        _sender: FactorySender<Self>,
+    ) -> Self {
+        Self {
+            digit,
+            errors,
+            max_errors,
+            digits_per_row,
+        }
+    }
+}
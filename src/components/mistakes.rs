@@ -0,0 +1,261 @@
+use adw::prelude::*;
+use relm4::{
+    adw, factory::FactoryVecDeque, gtk, ComponentParts, ComponentSender, RelmWidgetExt,
+    SimpleComponent,
+};
+
+use super::heatmap_digit::{heat_markup, HeatmapDigitModel};
+use crate::{
+    app::mistakes::MistakeStats,
+    digits::{digit_char, Digits},
+};
+
+const MAX_SHOWN_DIGITS: f64 = 2000.0;
+
+pub struct MistakesPageModel {
+    hidden: bool,
+    stats: MistakeStats,
+    digits: Digits,
+    shown_digits: usize,
+    digits_per_row: u8,
+
+    // widgets rebuilt on show
+    confusion_grid: gtk::Grid,
+    confusions_list: gtk::ListBox,
+
+    // factories
+    heatmap: FactoryVecDeque<HeatmapDigitModel>,
+}
+
+impl MistakesPageModel {
+    fn rebuild_heatmap(&mut self) {
+        let errors_per_position = &self.stats.errors_per_position;
+        let errors_at = |index: usize| *errors_per_position.get(&index).unwrap_or(&0);
+        let max_errors = errors_per_position
+            .range(..self.shown_digits)
+            .map(|(_, errors)| *errors)
+            .max()
+            .unwrap_or(0);
+
+        let mut guard = self.heatmap.guard();
+        guard.clear();
+        for (index, digit) in self.digits.iter().take(self.shown_digits).enumerate() {
+            guard.push_back((*digit, errors_at(index), max_errors, self.digits_per_row));
+        }
+    }
+
+    fn rebuild_confusions(&self) {
+        let base = self.stats.base as usize;
+        let matrix = &self.stats.confusion_matrix;
+        let max = matrix.iter().flatten().cloned().max().unwrap_or(0);
+
+        while let Some(child) = self.confusion_grid.first_child() {
+            self.confusion_grid.remove(&child);
+        }
+        // headers: expected digits in rows, typed digits in columns
+        for d in 0..base as u8 {
+            let header = |text: String| {
+                let label = gtk::Label::new(Some(&text));
+                label.add_css_class("heading");
+                label
+            };
+            let position = d as i32 + 1;
+            self.confusion_grid
+                .attach(&header(digit_char(d).to_string()), position, 0, 1, 1);
+            self.confusion_grid
+                .attach(&header(digit_char(d).to_string()), 0, position, 1, 1);
+        }
+        for (expected, row) in matrix.iter().take(base).enumerate() {
+            for (typed, count) in row.iter().take(base).enumerate() {
+                let (expected_char, typed_char) =
+                    (digit_char(expected as u8), digit_char(typed as u8));
+                let cell = gtk::Label::new(None);
+                cell.add_css_class("monospace");
+                cell.set_markup(&heat_markup(&count.to_string(), *count, max));
+                cell.set_tooltip_text(Some(&format!(
+                    "{count} time(s) typed {typed_char} instead of {expected_char}"
+                )));
+                self.confusion_grid
+                    .attach(&cell, typed as i32 + 1, expected as i32 + 1, 1, 1);
+            }
+        }
+
+        self.confusions_list.remove_all();
+        for (mistake, count) in &self.stats.common_confusions {
+            let row = adw::ActionRow::builder()
+                .title(format!(
+                    "Digit #{}: {} instead of {}",
+                    mistake.index + 1,
+                    digit_char(mistake.typed),
+                    digit_char(mistake.expected)
+                ))
+                .subtitle(format!("{count} time(s)"))
+                .build();
+            self.confusions_list.append(&row);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MistakesPageInput {
+    /// (mistake stats, digits of the sequence, digits per row)
+    Show((MistakeStats, Digits, u8)),
+    Hide,
+    SetShownDigits(usize),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for MistakesPageModel {
+    type Input = MistakesPageInput;
+    type Output = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Window {
+            set_modal: true,
+            set_title: Some("Mistakes"),
+            set_default_width: 600,
+            set_default_height: 600,
+
+            #[watch]
+            set_visible: !model.hidden,
+            connect_close_request[sender] => move |_| {
+                sender.input(MistakesPageInput::Hide);
+                gtk::glib::Propagation::Stop
+            },
+
+            adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::ViewSwitcher {
+                        set_stack: Some(&view_stack),
+                        set_policy: adw::ViewSwitcherPolicy::Wide,
+                    },
+
+                    pack_start = &gtk::SpinButton {
+                        set_tooltip_text: Some("Number of digits shown in the heatmap"),
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: &gtk::Adjustment::new(100.0, 10.0, MAX_SHOWN_DIGITS, 10.0, 0.0, 0.0), // set range and step increment
+                        connect_value_changed[sender] => move |spin_button| {
+                            sender.input(MistakesPageInput::SetShownDigits(spin_button.value() as usize));
+                        }
+                    },
+                },
+
+                #[name = "view_stack"]
+                adw::ViewStack {
+                    add_titled[Some("heatmap"), "Heatmap"] = &gtk::ScrolledWindow {
+                        set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                        set_vscrollbar_policy: gtk::PolicyType::Automatic,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 10,
+                            set_margin_all: 15,
+
+                            gtk::Label {
+                                set_css_classes: &["dim-label"],
+                                set_label: "The redder the digit, the more you got it wrong",
+                            },
+
+                            #[local_ref]
+                            heatmap_box -> gtk::Grid {
+                                set_halign: gtk::Align::Center,
+                                set_column_spacing: 5,
+                                set_row_spacing: 10,
+                            }
+                        }
+                    } -> {
+                        set_icon_name: Some("view-grid-symbolic"),
+                    },
+
+                    add_titled[Some("confusions"), "Confusions"] = &gtk::ScrolledWindow {
+                        set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 10,
+                            set_margin_all: 15,
+
+                            gtk::Label {
+                                set_css_classes: &["dim-label"],
+                                set_label: "Expected digits in rows, typed digits in columns",
+                            },
+
+                            #[local_ref]
+                            confusion_grid -> gtk::Grid {
+                                set_halign: gtk::Align::Center,
+                                set_column_spacing: 10,
+                                set_row_spacing: 10,
+                            },
+
+                            gtk::Label {
+                                set_label: "Most common confusions",
+                                set_css_classes: &["title-4"],
+                                set_halign: gtk::Align::Start,
+                                set_margin_top: 10,
+                            },
+
+                            #[local_ref]
+                            confusions_list -> gtk::ListBox {
+                                set_css_classes: &["boxed-list"],
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        }
+                    } -> {
+                        set_icon_name: Some("dialog-warning-symbolic"),
+                    },
+                }
+            }
+        }
+    }
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let heatmap = FactoryVecDeque::builder()
+            .launch(gtk::Grid::default())
+            .detach();
+
+        let model = MistakesPageModel {
+            hidden: true,
+            stats: MistakeStats::default(),
+            digits: Digits::embedded(),
+            shown_digits: 100,
+            digits_per_row: 10,
+            confusion_grid: gtk::Grid::default(),
+            confusions_list: gtk::ListBox::default(),
+            heatmap,
+        };
+
+        // inject to view!
+        let heatmap_box = model.heatmap.widget();
+        let confusion_grid = &model.confusion_grid;
+        let confusions_list = &model.confusions_list;
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _s: ComponentSender<Self>) {
+        match msg {
+            MistakesPageInput::Show((stats, digits, digits_per_row)) => {
+                self.stats = stats;
+                self.digits = digits;
+                self.digits_per_row = digits_per_row;
+                self.rebuild_heatmap();
+                self.rebuild_confusions();
+                self.hidden = false;
+            }
+            MistakesPageInput::Hide => self.hidden = true,
+            MistakesPageInput::SetShownDigits(shown_digits) => {
+                self.shown_digits = shown_digits;
+                self.rebuild_heatmap();
+            }
+        }
+    }
+}
@@ -1,4 +1,9 @@
 pub mod about;
 pub mod header;
+pub mod heatmap_digit;
+pub mod mistakes;
 pub mod pi_digit;
 pub mod preferences;
+pub mod profiles;
+pub mod search;
+pub mod statistics;
@@ -7,12 +7,7 @@ use relm4::{
     FactorySender,
 };
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-pub enum PiDigitState {
-    Right,
-    Wrong,
-    Placeholder,
-}
+use crate::{digits::digit_char, game::PiDigitState};
 
 #[derive(Clone)]
 pub struct PiDigitModel {
@@ -26,17 +21,21 @@ pub enum PiDigitInput {
     UpdateDigitState((u8, PiDigitState)),
 }
 
+/// position of the nth digit in a grid of `digits_per_row` columns
+pub fn digit_grid_position(index: usize, digits_per_row: u8) -> GridPosition {
+    let x = index % digits_per_row as usize;
+    let y = index / digits_per_row as usize;
+    GridPosition {
+        column: x as i32,
+        row: y as i32,
+        width: 1,
+        height: 1,
+    }
+}
+
 impl Position<GridPosition, DynamicIndex> for PiDigitModel {
     fn position(&self, index: &DynamicIndex) -> GridPosition {
-        let index = index.current_index();
-        let x = index % self.digits_per_row as usize;
-        let y = index / self.digits_per_row as usize;
-        GridPosition {
-            column: x as i32,
-            row: y as i32,
-            width: 1,
-            height: 1,
-        }
+        digit_grid_position(index.current_index(), self.digits_per_row)
     }
 }
 
@@ -56,11 +55,15 @@ impl FactoryComponent for PiDigitModel {
                 match self.state {
                     PiDigitState::Right => "suggested-action",
                     PiDigitState::Wrong => "destructive-action",
-                    PiDigitState::Placeholder => "raised",
+                    PiDigitState::Placeholder | PiDigitState::Hidden => "raised",
+                    PiDigitState::Context => "flat",
                 }
             ],
             #[watch]
-            set_label: &self.digit.to_string(),
+            set_label: &match self.state {
+                PiDigitState::Hidden => "?".to_string(),
+                _ => digit_char(self.digit).to_string(),
+            },
         }
     }
 
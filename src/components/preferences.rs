@@ -1,15 +1,41 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use adw::prelude::*;
 use relm4::{adw, gtk, ComponentParts, ComponentSender, SimpleComponent};
 
-use crate::app::{preferences::AppPreferences, AppMode};
+use crate::{
+    app::preferences::AppPreferences,
+    config,
+    digits::{base::BASES, custom, Sequence},
+    game::{quiz::QuizScoring, AppMode},
+};
 
 pub struct PreferencesPageModel {
     pub hidden: bool,
     pub mode: AppMode,
     pub timeout: Option<Duration>,
     pub digits_per_row: u8,
+    pub start_offset: usize,
+    pub drill_length: usize,
+    pub drill_widen_after: u8,
+    pub digits_len: usize,
+    pub sequence: Sequence,
+    pub base: u8,
+    pub quiz_range: usize,
+    pub quiz_length: u8,
+    pub quiz_questions: u8,
+    pub quiz_scoring: QuizScoring,
+    pub cloze_length: usize,
+    pub cloze_density: u8,
+    pub reverse_length: usize,
+    pub choice_length: u8,
+    pub choice_context: u8,
+    /// the built-in then the imported sequences, in the order of the combo row
+    pub sequences: Vec<Sequence>,
+
+    // widgets
+    window: adw::PreferencesWindow,
+    sequence_names: gtk::StringList,
 }
 
 #[derive(Debug)]
@@ -19,6 +45,31 @@ pub enum PreferencesPageInput {
     SelectMode(AppMode),
     SelectTimeout(f32),
     SetDigitsPerRow(u8),
+    SetStartOffset(usize),
+    SetDrillLength(usize),
+    SetDrillWidenAfter(u8),
+    SetDigitsLen(usize),
+    /// index in the combo row
+    SelectSequence(usize),
+    /// index in `BASES`
+    SelectBase(usize),
+    OpenImportDialog,
+    /// a sequence was imported, select it
+    AddSequence(Sequence),
+    /// the mode was changed from elsewhere in the app, select it
+    SyncMode(AppMode),
+    /// the preferences of another profile are used, show them
+    SyncPreferences(AppPreferences),
+    SetQuizRange(usize),
+    SetQuizLength(u8),
+    SetQuizQuestions(u8),
+    /// index in the combo row
+    SelectQuizScoring(usize),
+    SetClozeLength(usize),
+    SetClozeDensity(u8),
+    SetReverseLength(usize),
+    SetChoiceLength(u8),
+    SetChoiceContext(u8),
 }
 
 #[derive(Debug)]
@@ -27,6 +78,22 @@ pub enum PreferencesPageOutput {
     SetMode(AppMode),
     SetTimeout(Option<Duration>),
     SetDigitsPerRow(u8),
+    SetStartOffset(usize),
+    SetDrillLength(usize),
+    SetDrillWidenAfter(u8),
+    SetDigitsLen(usize),
+    SetSequence(Sequence),
+    SetBase(u8),
+    ImportSequence(PathBuf),
+    SetQuizRange(usize),
+    SetQuizLength(u8),
+    SetQuizQuestions(u8),
+    SetQuizScoring(QuizScoring),
+    SetClozeLength(usize),
+    SetClozeDensity(u8),
+    SetReverseLength(usize),
+    SetChoiceLength(u8),
+    SetChoiceContext(u8),
 }
 
 #[relm4::component(pub)]
@@ -54,7 +121,7 @@ impl SimpleComponent for PreferencesPageModel {
                     set_title: "App settings",
                     adw::ComboRow {
                         set_title: "App Mode",
-                        set_model: Some(&gtk::StringList::new(&["Blind", "Learn", "InstantDeath"])),
+                        set_model: Some(&gtk::StringList::new(&["Blind", "Learn", "InstantDeath", "Drill", "Review", "Quiz", "Cloze", "Reverse", "Choice"])),
 
                         #[watch]
                         set_selected: model.mode as u32,
@@ -66,6 +133,12 @@ impl SimpleComponent for PreferencesPageModel {
                                         "Blind" => AppMode::Blind,
                                         "Learn" => AppMode::Learn,
                                         "InstantDeath" => AppMode::InstantDeath,
+                                        "Drill" => AppMode::Drill,
+                                        "Review" => AppMode::Review,
+                                        "Quiz" => AppMode::Quiz,
+                                        "Cloze" => AppMode::Cloze,
+                                        "Reverse" => AppMode::Reverse,
+                                        "Choice" => AppMode::Choice,
                                         _ => AppMode::Learn // should be unreachable
                                     };
                                     sender.input(PreferencesPageInput::SelectMode(selected_mode));
@@ -73,6 +146,38 @@ impl SimpleComponent for PreferencesPageModel {
                             }
                         }
                     },
+                    adw::ComboRow {
+                        set_title: "Sequence",
+                        set_subtitle: "Constant whose digits are played, each has its own stats",
+                        set_model: Some(&sequence_names),
+
+                        #[watch]
+                        set_selected: model.sequences.iter().position(|s| *s == model.sequence).unwrap_or_default() as u32,
+                        connect_selected_notify[sender] => move |combo_row| {
+                            sender.input(PreferencesPageInput::SelectSequence(combo_row.selected() as usize));
+                        }
+                    },
+                    adw::ComboRow {
+                        set_title: "Base",
+                        set_subtitle: "Digits past 9 are typed and shown as letters",
+                        set_model: Some(&gtk::StringList::new(&["Binary", "Octal", "Decimal", "Duodecimal", "Hexadecimal"])),
+
+                        #[watch]
+                        set_selected: BASES.iter().position(|b| *b == model.base).unwrap_or_default() as u32,
+                        connect_selected_notify[sender] => move |combo_row| {
+                            sender.input(PreferencesPageInput::SelectBase(combo_row.selected() as usize));
+                        }
+                    },
+                    adw::ActionRow {
+                        set_title: "Import a sequence",
+                        set_subtitle: "From a text file of digits in the selected base, spaces and line breaks are ignored",
+                        add_suffix = &gtk::Button {
+                            set_icon_name: "document-open-symbolic",
+                            set_valign: gtk::Align::Center,
+                            set_css_classes: &["flat"],
+                            connect_clicked => PreferencesPageInput::OpenImportDialog,
+                        },
+                    },
                     adw::SpinRow {
                         set_title: "Timeout",
                         set_subtitle: "in seconds (0 to disable)",
@@ -96,6 +201,180 @@ impl SimpleComponent for PreferencesPageModel {
                         connect_value_notify[sender] => move |spin_row| {
                             sender.input(PreferencesPageInput::SetDigitsPerRow(spin_row.value().round() as u8));
                         }
+                    },
+                    adw::SpinRow {
+                        set_title: "Start offset",
+                        set_subtitle: "Number of pi digits skipped at the start of a game",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(0.0,0.0,config::MAX_START_OFFSET as f64,10.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.start_offset as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetStartOffset(spin_row.value().round() as usize));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Known digits",
+                        set_subtitle: "Number of digits to play with, computed when not embedded",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(1_000_000.0,1000.0,config::MAX_DIGITS as f64,100_000.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.digits_len as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetDigitsLen(spin_row.value().round() as usize));
+                        }
+                    }
+                },
+                add = &adw::PreferencesGroup {
+                    set_title: "Drill mode",
+                    set_description: Some("The window starts at the start offset"),
+                    adw::SpinRow {
+                        set_title: "Window length",
+                        set_subtitle: "Number of pi digits repeated",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(20.0,1.0,1000.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.drill_length as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetDrillLength(spin_row.value().round() as usize));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Widen after",
+                        set_subtitle: &format!("Clean runs in a row before adding {} digits (0 to disable)", config::DRILL_WIDEN_STEP),
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(3.0,0.0,255.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.drill_widen_after as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetDrillWidenAfter(spin_row.value().round() as u8));
+                        }
+                    }
+                },
+                add = &adw::PreferencesGroup {
+                    set_title: "Quiz mode",
+                    set_description: Some("The digits at random positions are asked without the ones before"),
+                    adw::SpinRow {
+                        set_title: "Known digits",
+                        set_subtitle: "The questions are picked in these first digits",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(100.0,1.0,config::MAX_DIGITS as f64,10.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.quiz_range as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetQuizRange(spin_row.value().round() as usize));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Question length",
+                        set_subtitle: "Number of digits asked per question",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(10.0,1.0,100.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.quiz_length as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetQuizLength(spin_row.value().round() as u8));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Questions",
+                        set_subtitle: "Number of questions per quiz",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(10.0,1.0,100.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.quiz_questions as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetQuizQuestions(spin_row.value().round() as u8));
+                        }
+                    },
+                    adw::ComboRow {
+                        set_title: "Scoring",
+                        set_model: Some(&gtk::StringList::new(&["A point per right digit", "A point per perfect answer"])),
+
+                        #[watch]
+                        set_selected: model.quiz_scoring as u32,
+                        connect_selected_notify[sender] => move |combo_row| {
+                            sender.input(PreferencesPageInput::SelectQuizScoring(combo_row.selected() as usize));
+                        }
+                    }
+                },
+                add = &adw::PreferencesGroup {
+                    set_title: "Cloze mode",
+                    set_description: Some("The window starts at the start offset, only its hidden digits are typed"),
+                    adw::SpinRow {
+                        set_title: "Window length",
+                        set_subtitle: "Number of pi digits shown",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(50.0,1.0,1000.0,10.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.cloze_length as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetClozeLength(spin_row.value().round() as usize));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Gap density",
+                        set_subtitle: "Percentage of the digits that are hidden",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(20.0,1.0,100.0,5.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.cloze_density as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetClozeDensity(spin_row.value().round() as u8));
+                        }
+                    }
+                },
+                add = &adw::PreferencesGroup {
+                    set_title: "Reverse mode",
+                    set_description: Some("The digits are typed backwards, down to the start offset"),
+                    adw::SpinRow {
+                        set_title: "Length",
+                        set_subtitle: "Number of pi digits typed backwards",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(20.0,1.0,1000.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.reverse_length as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetReverseLength(spin_row.value().round() as usize));
+                        }
+                    }
+                },
+                add = &adw::PreferencesGroup {
+                    set_title: "Choice mode",
+                    set_description: Some("The next digits are picked among four candidates, with the mouse or the keys 1 to 4"),
+                    adw::SpinRow {
+                        set_title: "Chunk length",
+                        set_subtitle: "Number of pi digits per candidate",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(4.0,1.0,20.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.choice_length as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetChoiceLength(spin_row.value().round() as u8));
+                        }
+                    },
+                    adw::SpinRow {
+                        set_title: "Context",
+                        set_subtitle: "Number of digits shown before the chunk to pick",
+                        set_numeric: true,
+                        set_digits: 0,
+                        set_adjustment: Some(&gtk::Adjustment::new(10.0,0.0,50.0,1.0,0.0,0.0)), // set range and step increment
+                        #[watch]
+                        set_value: model.choice_context as f64,
+                        connect_value_notify[sender] => move |spin_row| {
+                            sender.input(PreferencesPageInput::SetChoiceContext(spin_row.value().round() as u8));
+                        }
                     }
                 }
             }
@@ -112,7 +391,32 @@ impl SimpleComponent for PreferencesPageModel {
             mode: pref.mode,
             timeout: pref.timeout,
             digits_per_row: pref.digits_per_row,
+            start_offset: pref.start_offset,
+            drill_length: pref.drill_length,
+            drill_widen_after: pref.drill_widen_after,
+            digits_len: pref.digits_len,
+            sequence: pref.sequence,
+            base: pref.base,
+            quiz_range: pref.quiz_range,
+            quiz_length: pref.quiz_length,
+            quiz_questions: pref.quiz_questions,
+            quiz_scoring: pref.quiz_scoring,
+            cloze_length: pref.cloze_length,
+            cloze_density: pref.cloze_density,
+            reverse_length: pref.reverse_length,
+            choice_length: pref.choice_length,
+            choice_context: pref.choice_context,
+            sequences: Sequence::ALL.into_iter().chain(custom::list()).collect(),
+            window: root.clone(),
+            sequence_names: gtk::StringList::default(),
         };
+        for sequence in &model.sequences {
+            model.sequence_names.append(sequence.get().name());
+        }
+
+        // inject to view!
+        let sequence_names = &model.sequence_names;
+
         let widgets = view_output!();
         ComponentParts { model, widgets }
     }
@@ -122,6 +426,9 @@ impl SimpleComponent for PreferencesPageModel {
             PreferencesPageInput::Show => self.hidden = false,
             PreferencesPageInput::Hide => self.hidden = true,
             PreferencesPageInput::SelectMode(mode) => {
+                if self.mode == mode {
+                    return; // avoid restarting the game
+                }
                 self.mode = mode;
                 let _ = sender.output(PreferencesPageOutput::SetMode(mode)); // todo: if failed send toast to main app
             }
@@ -137,6 +444,129 @@ impl SimpleComponent for PreferencesPageModel {
                 self.digits_per_row = digits_per_row;
                 let _ = sender.output(PreferencesPageOutput::SetDigitsPerRow(digits_per_row));
             }
+            PreferencesPageInput::SetStartOffset(start_offset) => {
+                self.start_offset = start_offset;
+                let _ = sender.output(PreferencesPageOutput::SetStartOffset(start_offset));
+            }
+            PreferencesPageInput::SetDrillLength(drill_length) => {
+                self.drill_length = drill_length;
+                let _ = sender.output(PreferencesPageOutput::SetDrillLength(drill_length));
+            }
+            PreferencesPageInput::SetDrillWidenAfter(widen_after) => {
+                self.drill_widen_after = widen_after;
+                let _ = sender.output(PreferencesPageOutput::SetDrillWidenAfter(widen_after));
+            }
+            PreferencesPageInput::SetDigitsLen(digits_len) => {
+                if self.digits_len == digits_len {
+                    return; // avoid computing the digits again
+                }
+                self.digits_len = digits_len;
+                let _ = sender.output(PreferencesPageOutput::SetDigitsLen(digits_len));
+            }
+            PreferencesPageInput::SelectSequence(index) => {
+                let sequence = match self.sequences.get(index) {
+                    Some(sequence) if *sequence != self.sequence => *sequence,
+                    _ => return, // avoid loading the digits again
+                };
+                self.sequence = sequence;
+                let _ = sender.output(PreferencesPageOutput::SetSequence(sequence));
+            }
+            PreferencesPageInput::SelectBase(index) => {
+                let base = match BASES.get(index) {
+                    Some(base) if *base != self.base => *base,
+                    _ => return, // avoid loading the digits again
+                };
+                self.base = base;
+                let _ = sender.output(PreferencesPageOutput::SetBase(base));
+            }
+            PreferencesPageInput::OpenImportDialog => {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Import a sequence")
+                    .modal(true)
+                    .build();
+                dialog.open(
+                    Some(&self.window),
+                    None::<&gtk::gio::Cancellable>,
+                    move |result| {
+                        if let Some(path) = result.ok().and_then(|file| file.path()) {
+                            let _ = sender.output(PreferencesPageOutput::ImportSequence(path));
+                        }
+                    },
+                );
+            }
+            PreferencesPageInput::AddSequence(sequence) => {
+                // appended so that the selection does not move
+                self.sequences.push(sequence);
+                self.sequence_names.append(sequence.get().name());
+                self.sequence = sequence;
+            }
+            PreferencesPageInput::SyncMode(mode) => self.mode = mode,
+            PreferencesPageInput::SyncPreferences(pref) => {
+                self.mode = pref.mode;
+                self.timeout = pref.timeout;
+                self.digits_per_row = pref.digits_per_row;
+                self.start_offset = pref.start_offset;
+                self.drill_length = pref.drill_length;
+                self.drill_widen_after = pref.drill_widen_after;
+                self.digits_len = pref.digits_len;
+                self.sequence = pref.sequence;
+                self.base = pref.base;
+                self.quiz_range = pref.quiz_range;
+                self.quiz_length = pref.quiz_length;
+                self.quiz_questions = pref.quiz_questions;
+                self.quiz_scoring = pref.quiz_scoring;
+                self.cloze_length = pref.cloze_length;
+                self.cloze_density = pref.cloze_density;
+                self.reverse_length = pref.reverse_length;
+                self.choice_length = pref.choice_length;
+                self.choice_context = pref.choice_context;
+                // the sequences added by a data import
+                for sequence in custom::list() {
+                    if !self.sequences.contains(&sequence) {
+                        self.sequences.push(sequence);
+                        self.sequence_names.append(sequence.get().name());
+                    }
+                }
+            }
+            PreferencesPageInput::SetQuizRange(quiz_range) => {
+                self.quiz_range = quiz_range;
+                let _ = sender.output(PreferencesPageOutput::SetQuizRange(quiz_range));
+            }
+            PreferencesPageInput::SetQuizLength(quiz_length) => {
+                self.quiz_length = quiz_length;
+                let _ = sender.output(PreferencesPageOutput::SetQuizLength(quiz_length));
+            }
+            PreferencesPageInput::SetQuizQuestions(quiz_questions) => {
+                self.quiz_questions = quiz_questions;
+                let _ = sender.output(PreferencesPageOutput::SetQuizQuestions(quiz_questions));
+            }
+            PreferencesPageInput::SelectQuizScoring(index) => {
+                let Ok(quiz_scoring) = QuizScoring::try_from(index as u8) else {
+                    return;
+                };
+                self.quiz_scoring = quiz_scoring;
+                let _ = sender.output(PreferencesPageOutput::SetQuizScoring(quiz_scoring));
+            }
+            PreferencesPageInput::SetClozeLength(cloze_length) => {
+                self.cloze_length = cloze_length;
+                let _ = sender.output(PreferencesPageOutput::SetClozeLength(cloze_length));
+            }
+            PreferencesPageInput::SetClozeDensity(cloze_density) => {
+                self.cloze_density = cloze_density;
+                let _ = sender.output(PreferencesPageOutput::SetClozeDensity(cloze_density));
+            }
+            PreferencesPageInput::SetReverseLength(reverse_length) => {
+                self.reverse_length = reverse_length;
+                let _ = sender.output(PreferencesPageOutput::SetReverseLength(reverse_length));
+            }
+            PreferencesPageInput::SetChoiceLength(choice_length) => {
+                self.choice_length = choice_length;
+                let _ = sender.output(PreferencesPageOutput::SetChoiceLength(choice_length));
+            }
+            PreferencesPageInput::SetChoiceContext(choice_context) => {
+                self.choice_context = choice_context;
+                let _ = sender.output(PreferencesPageOutput::SetChoiceContext(choice_context));
+            }
         }
     }
 }
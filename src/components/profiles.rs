@@ -0,0 +1,190 @@
+use adw::prelude::*;
+use relm4::{adw, gtk, ComponentParts, ComponentSender, RelmWidgetExt, SimpleComponent};
+
+use crate::app::storage::{self, is_valid_profile_name};
+
+pub struct ProfilesPageModel {
+    hidden: bool,
+    /// the default profile first, in the order of the list
+    profiles: Vec<String>,
+    active: String,
+    /// name typed for a new profile
+    new_name: String,
+
+    // widgets rebuilt when shown
+    profiles_list: gtk::ListBox,
+}
+
+impl ProfilesPageModel {
+    fn rebuild_profiles(&self) {
+        self.profiles_list.remove_all();
+        for profile in &self.profiles {
+            let row = adw::ActionRow::builder()
+                .title(profile)
+                .activatable(true)
+                .build();
+            if *profile == self.active {
+                row.add_suffix(&gtk::Image::from_icon_name("object-select-symbolic"));
+            }
+            self.profiles_list.append(&row);
+        }
+    }
+
+    fn hint(&self) -> &str {
+        let new_name = self.new_name.trim();
+        if new_name.is_empty() {
+            "Each profile has its own preferences, history and review schedule"
+        } else if self
+            .profiles
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(new_name))
+        {
+            "This profile already exists"
+        } else if !is_valid_profile_name(new_name) {
+            "Only letters, digits, spaces, - and _ are allowed, up to 32 characters"
+        } else {
+            "Press enter to create the profile and switch to it"
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfilesPageInput {
+    Show,
+    Hide,
+    /// index in the profiles list
+    Select(usize),
+    SetNewName(String),
+    Create,
+}
+
+#[derive(Debug)]
+pub enum ProfilesPageOutput {
+    Switch(String),
+    /// create the profile and switch to it
+    Create(String),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ProfilesPageModel {
+    type Input = ProfilesPageInput;
+    type Output = ProfilesPageOutput;
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Window {
+            set_modal: true,
+            set_title: Some("Profiles"),
+            set_default_width: 400,
+            set_default_height: 450,
+
+            #[watch]
+            set_visible: !model.hidden,
+            connect_close_request[sender] => move |_| {
+                sender.input(ProfilesPageInput::Hide);
+                gtk::glib::Propagation::Stop
+            },
+
+            adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {},
+
+                gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 10,
+                        set_margin_all: 15,
+
+                        #[local_ref]
+                        profiles_list -> gtk::ListBox {
+                            set_css_classes: &["boxed-list"],
+                            set_selection_mode: gtk::SelectionMode::None,
+                            connect_row_activated[sender] => move |_, row| {
+                                sender.input(ProfilesPageInput::Select(row.index() as usize));
+                            }
+                        },
+
+                        gtk::ListBox {
+                            set_css_classes: &["boxed-list"],
+                            set_selection_mode: gtk::SelectionMode::None,
+
+                            adw::EntryRow {
+                                set_title: "New profile",
+                                set_show_apply_button: true,
+                                connect_changed[sender] => move |entry| {
+                                    sender.input(ProfilesPageInput::SetNewName(entry.text().to_string()));
+                                },
+                                connect_apply => ProfilesPageInput::Create,
+                            },
+                        },
+
+                        gtk::Label {
+                            set_css_classes: &["dim-label"],
+                            set_wrap: true,
+                            #[watch]
+                            set_label: model.hint(),
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ProfilesPageModel {
+            hidden: true,
+            profiles: vec![],
+            active: String::new(),
+            new_name: String::new(),
+            profiles_list: gtk::ListBox::default(),
+        };
+
+        // inject to view!
+        let profiles_list = &model.profiles_list;
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            ProfilesPageInput::Show => {
+                // profiles may have been created since the last time
+                self.profiles = storage::list_profiles();
+                self.active = storage::active_profile();
+                self.rebuild_profiles();
+                self.hidden = false;
+            }
+            ProfilesPageInput::Hide => self.hidden = true,
+            ProfilesPageInput::Select(index) => {
+                let Some(profile) = self.profiles.get(index) else {
+                    return;
+                };
+                self.hidden = true;
+                if *profile != self.active {
+                    let _ = sender.output(ProfilesPageOutput::Switch(profile.clone()));
+                }
+            }
+            ProfilesPageInput::SetNewName(new_name) => self.new_name = new_name,
+            ProfilesPageInput::Create => {
+                let new_name = self.new_name.trim();
+                if !is_valid_profile_name(new_name)
+                    || self
+                        .profiles
+                        .iter()
+                        .any(|p| p.eq_ignore_ascii_case(new_name))
+                {
+                    return; // the hint tells why
+                }
+                self.hidden = true;
+                let _ = sender.output(ProfilesPageOutput::Create(new_name.to_string()));
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use adw::prelude::*;
+use relm4::{
+    adw, factory::FactoryVecDeque, gtk, ComponentParts, ComponentSender, RelmWidgetExt,
+    SimpleComponent,
+};
+
+use super::pi_digit::PiDigitModel;
+use crate::{
+    digits::{
+        digit_char,
+        search::{parse_query, SearchIndex},
+        Digits,
+    },
+    game::PiDigitState,
+};
+
+const MAX_LISTED_OCCURRENCES: usize = 100;
+/// digits shown around the selected occurrence
+const CONTEXT_DIGITS: usize = 20;
+/// digits shown around each occurrence in the list
+const PREVIEW_DIGITS: usize = 5;
+
+pub struct SearchPageModel {
+    hidden: bool,
+    /// built when the page is shown, for the digits being played
+    index: Option<SearchIndex>,
+    digits_per_row: u8,
+    /// text typed in the search field
+    query: String,
+    query_len: usize,
+    occurrences: Vec<usize>,
+    /// position of the occurrence shown in the grid
+    selected: Option<usize>,
+    status: String,
+
+    // widgets rebuilt on search
+    results_list: gtk::ListBox,
+
+    // factories
+    context: FactoryVecDeque<PiDigitModel>,
+}
+
+impl SearchPageModel {
+    fn search(&mut self) {
+        self.occurrences.clear();
+        self.query_len = 0;
+        self.select(None);
+
+        let Some(index) = &self.index else { return };
+        let (base, len) = (index.digits().base(), index.digits().len());
+        self.status = match parse_query(&self.query, base) {
+            Ok(query) if query.is_empty() => "Type a number to find it in the digits".to_string(),
+            Ok(query) => {
+                self.query_len = query.len();
+                self.occurrences = index.find(&query);
+                match self.occurrences.len() {
+                    0 => format!("Not found in the first {len} digits"),
+                    count if count > MAX_LISTED_OCCURRENCES => format!(
+                        "{count} occurrences, the first {MAX_LISTED_OCCURRENCES} are listed"
+                    ),
+                    count => format!("{count} occurrence(s)"),
+                }
+            }
+            Err(_) => format!("Only base {base} digits can be searched"),
+        };
+        self.rebuild_results();
+        if let Some(first) = self.occurrences.first() {
+            self.select(Some(*first));
+        }
+    }
+
+    fn rebuild_results(&self) {
+        let Some(index) = &self.index else { return };
+        let digits = index.digits();
+        let text = |start: usize, end: usize| -> String {
+            digits[start..end.min(digits.len())]
+                .iter()
+                .map(|d| digit_char(*d))
+                .collect()
+        };
+
+        self.results_list.remove_all();
+        for position in self.occurrences.iter().take(MAX_LISTED_OCCURRENCES) {
+            let end = position + self.query_len;
+            let row = adw::ActionRow::builder()
+                .title(format!("Digit #{}", position + 1))
+                .subtitle(format!(
+                    "…{} <b>{}</b> {}…",
+                    text(position.saturating_sub(PREVIEW_DIGITS), *position),
+                    text(*position, end),
+                    text(end, end + PREVIEW_DIGITS)
+                ))
+                .activatable(true)
+                .build();
+            self.results_list.append(&row);
+        }
+    }
+
+    /// show the occurrence and its surroundings in the grid
+    fn select(&mut self, position: Option<usize>) {
+        self.selected = position;
+        let mut guard = self.context.guard();
+        guard.clear();
+
+        let (Some(position), Some(index)) = (position, &self.index) else {
+            return;
+        };
+        let digits = index.digits();
+        let start = position.saturating_sub(CONTEXT_DIGITS);
+        let end = (position + self.query_len + CONTEXT_DIGITS).min(digits.len());
+        for (i, digit) in digits[start..end].iter().enumerate() {
+            let matched = (position..position + self.query_len).contains(&(start + i));
+            let state = match matched {
+                true => PiDigitState::Right,
+                false => PiDigitState::Context,
+            };
+            guard.push_back((*digit, state, self.digits_per_row));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SearchPageInput {
+    /// (digits of the sequence, digits per row)
+    Show((Digits, u8)),
+    Hide,
+    Search(String),
+    /// index in the results list
+    SelectResult(usize),
+    Drill,
+}
+
+#[derive(Debug)]
+pub enum SearchPageOutput {
+    /// start a drill at this position
+    Drill(usize),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for SearchPageModel {
+    type Input = SearchPageInput;
+    type Output = SearchPageOutput;
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Window {
+            set_modal: true,
+            set_title: Some("Find a number"),
+            set_default_width: 600,
+            set_default_height: 600,
+
+            #[watch]
+            set_visible: !model.hidden,
+            connect_close_request[sender] => move |_| {
+                sender.input(SearchPageInput::Hide);
+                gtk::glib::Propagation::Stop
+            },
+
+            adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {},
+
+                gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 10,
+                        set_margin_all: 15,
+
+                        gtk::SearchEntry {
+                            set_placeholder_text: Some("Birthday, PIN, phone number..."),
+                            connect_search_changed[sender] => move |entry| {
+                                sender.input(SearchPageInput::Search(entry.text().to_string()));
+                            }
+                        },
+
+                        gtk::Label {
+                            set_css_classes: &["dim-label"],
+                            #[watch]
+                            set_label: &model.status,
+                        },
+
+                        #[local_ref]
+                        context_box -> gtk::Grid {
+                            set_halign: gtk::Align::Center,
+                            set_column_spacing: 5,
+                            set_row_spacing: 10,
+                        },
+
+                        gtk::Button {
+                            set_css_classes: &["suggested-action", "pill"],
+                            set_label: "Drill from here",
+                            set_halign: gtk::Align::Center,
+                            #[watch]
+                            set_visible: model.selected.is_some(),
+                            connect_clicked => SearchPageInput::Drill
+                        },
+
+                        #[local_ref]
+                        results_list -> gtk::ListBox {
+                            set_css_classes: &["boxed-list"],
+                            set_selection_mode: gtk::SelectionMode::None,
+                            #[watch]
+                            set_visible: !model.occurrences.is_empty(),
+                            connect_row_activated[sender] => move |_, row| {
+                                sender.input(SearchPageInput::SelectResult(row.index() as usize));
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let context = FactoryVecDeque::builder()
+            .launch(gtk::Grid::default())
+            .detach();
+
+        let model = SearchPageModel {
+            hidden: true,
+            index: None,
+            digits_per_row: 10,
+            query: String::new(),
+            query_len: 0,
+            occurrences: vec![],
+            selected: None,
+            status: "Type a number to find it in the digits".to_string(),
+            results_list: gtk::ListBox::default(),
+            context,
+        };
+
+        // inject to view!
+        let context_box = model.context.widget();
+        let results_list = &model.results_list;
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            SearchPageInput::Show((digits, digits_per_row)) => {
+                self.digits_per_row = digits_per_row;
+                if self.index.as_ref().map(|index| index.digits()) != Some(&digits) {
+                    // the sequence changed since the last search
+                    self.index = Some(SearchIndex::new(digits));
+                    self.search();
+                } else {
+                    self.select(self.selected);
+                }
+                self.hidden = false;
+            }
+            SearchPageInput::Hide => self.hidden = true,
+            SearchPageInput::Search(query) => {
+                self.query = query;
+                self.search();
+            }
+            SearchPageInput::SelectResult(index) => {
+                if let Some(position) = self.occurrences.get(index) {
+                    self.select(Some(*position));
+                }
+            }
+            SearchPageInput::Drill => {
+                let Some(position) = self.selected else {
+                    return;
+                };
+                self.hidden = true;
+                let _ = sender.output(SearchPageOutput::Drill(position));
+            }
+        }
+    }
+}
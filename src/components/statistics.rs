@@ -0,0 +1,265 @@
+use adw::prelude::*;
+use relm4::{
+    adw,
+    gtk::{self, cairo},
+    ComponentParts, ComponentSender, RelmWidgetExt, SimpleComponent,
+};
+
+use crate::app::history::DayStats;
+
+const CHART_HEIGHT: i32 = 150;
+const CHART_PADDING: f64 = 10.0;
+
+#[derive(Debug, Copy, Clone)]
+enum ChartKind {
+    Line,
+    Bars,
+}
+
+/// Plot the values from left to right, scaled to the drawing area
+fn draw_chart(
+    ctx: &cairo::Context,
+    width: i32,
+    height: i32,
+    values: &[f64],
+    kind: ChartKind,
+    (r, g, b): (f64, f64, f64),
+) {
+    let (width, height) = (
+        width as f64 - 2.0 * CHART_PADDING,
+        height as f64 - 2.0 * CHART_PADDING,
+    );
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    if values.is_empty() || max <= 0.0 || width <= 0.0 || height <= 0.0 {
+        return;
+    }
+
+    // axis
+    ctx.set_source_rgba(0.5, 0.5, 0.5, 0.5);
+    ctx.set_line_width(1.0);
+    ctx.move_to(CHART_PADDING, CHART_PADDING);
+    ctx.line_to(CHART_PADDING, CHART_PADDING + height);
+    ctx.line_to(CHART_PADDING + width, CHART_PADDING + height);
+    let _ = ctx.stroke();
+
+    let step = width / values.len() as f64;
+    let y_of = |value: f64| CHART_PADDING + height * (1.0 - value / max);
+
+    ctx.set_source_rgb(r, g, b);
+    match kind {
+        ChartKind::Bars => {
+            for (i, value) in values.iter().enumerate() {
+                let x = CHART_PADDING + i as f64 * step;
+                ctx.rectangle(
+                    x + step * 0.1,
+                    y_of(*value),
+                    step * 0.8,
+                    height * value / max,
+                );
+            }
+            let _ = ctx.fill();
+        }
+        ChartKind::Line => {
+            ctx.set_line_width(2.0);
+            for (i, value) in values.iter().enumerate() {
+                let x = CHART_PADDING + (i as f64 + 0.5) * step;
+                ctx.line_to(x, y_of(*value));
+            }
+            let _ = ctx.stroke();
+
+            for (i, value) in values.iter().enumerate() {
+                let x = CHART_PADDING + (i as f64 + 0.5) * step;
+                ctx.arc(x, y_of(*value), 3.0, 0.0, std::f64::consts::TAU);
+                let _ = ctx.fill();
+            }
+        }
+    }
+}
+
+pub struct StatisticsPageModel {
+    hidden: bool,
+    days: Vec<DayStats>,
+
+    // charts
+    best_chart: gtk::DrawingArea,
+    sessions_chart: gtk::DrawingArea,
+    speed_chart: gtk::DrawingArea,
+    errors_chart: gtk::DrawingArea,
+}
+
+impl StatisticsPageModel {
+    fn redraw_charts(&self) {
+        let charts = [
+            (&self.best_chart, ChartKind::Line, (0.21, 0.52, 0.89)),
+            (&self.sessions_chart, ChartKind::Bars, (0.15, 0.64, 0.41)),
+            (&self.speed_chart, ChartKind::Line, (0.90, 0.65, 0.04)),
+            (&self.errors_chart, ChartKind::Line, (0.88, 0.11, 0.14)),
+        ];
+        let series: [Vec<f64>; 4] = [
+            self.days.iter().map(|d| d.best_so_far as f64).collect(),
+            self.days.iter().map(|d| d.sessions as f64).collect(),
+            self.days.iter().map(|d| d.avg_digits_per_minute).collect(),
+            self.days.iter().map(|d| d.error_rate * 100.0).collect(),
+        ];
+
+        for ((chart, kind, color), values) in charts.into_iter().zip(series) {
+            chart.set_draw_func(move |_, ctx, width, height| {
+                draw_chart(ctx, width, height, &values, kind, color)
+            });
+        }
+    }
+
+    fn summary(&self) -> String {
+        let (first, last) = match (self.days.first(), self.days.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return String::new(),
+        };
+        format!(
+            "{} games over {} day(s), best: {} digits from memory",
+            self.days.iter().map(|d| d.sessions).sum::<usize>(),
+            last.day - first.day + 1,
+            last.best_so_far
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum StatisticsPageInput {
+    Show(Vec<DayStats>),
+    Hide,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for StatisticsPageModel {
+    type Input = StatisticsPageInput;
+    type Output = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Window {
+            set_modal: true,
+            set_title: Some("Statistics"),
+            set_default_width: 600,
+            set_default_height: 700,
+
+            #[watch]
+            set_visible: !model.hidden,
+            connect_close_request[sender] => move |_| {
+                sender.input(StatisticsPageInput::Hide);
+                gtk::glib::Propagation::Stop
+            },
+
+            adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {},
+
+                gtk::Stack {
+                    #[watch]
+                    set_visible_child_name: if model.days.is_empty() { "empty" } else { "charts" },
+
+                    add_named[Some("empty")] = &adw::StatusPage {
+                        set_icon_name: Some("logo"),
+                        set_title: "No statistics yet",
+                        set_description: Some("Finish a game to track your progress"),
+                    },
+
+                    add_named[Some("charts")] = &gtk::ScrolledWindow {
+                        set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 5,
+                            set_margin_all: 15,
+
+                            gtk::Label {
+                                set_css_classes: &["dim-label"],
+                                #[watch]
+                                set_label: &model.summary(),
+                                set_margin_bottom: 10,
+                            },
+
+                            gtk::Label {
+                                set_label: "Best digits reached from memory",
+                                set_css_classes: &["title-4"],
+                                set_halign: gtk::Align::Start,
+                            },
+                            #[local_ref]
+                            best_chart -> gtk::DrawingArea {
+                                set_content_height: CHART_HEIGHT,
+                                set_hexpand: true,
+                            },
+
+                            gtk::Label {
+                                set_label: "Games per day",
+                                set_css_classes: &["title-4"],
+                                set_halign: gtk::Align::Start,
+                            },
+                            #[local_ref]
+                            sessions_chart -> gtk::DrawingArea {
+                                set_content_height: CHART_HEIGHT,
+                                set_hexpand: true,
+                            },
+
+                            gtk::Label {
+                                set_label: "Average digits per minute",
+                                set_css_classes: &["title-4"],
+                                set_halign: gtk::Align::Start,
+                            },
+                            #[local_ref]
+                            speed_chart -> gtk::DrawingArea {
+                                set_content_height: CHART_HEIGHT,
+                                set_hexpand: true,
+                            },
+
+                            gtk::Label {
+                                set_label: "Error rate (%)",
+                                set_css_classes: &["title-4"],
+                                set_halign: gtk::Align::Start,
+                            },
+                            #[local_ref]
+                            errors_chart -> gtk::DrawingArea {
+                                set_content_height: CHART_HEIGHT,
+                                set_hexpand: true,
+                            },
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    fn init(
+        _: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = StatisticsPageModel {
+            hidden: true,
+            days: vec![],
+            best_chart: gtk::DrawingArea::default(),
+            sessions_chart: gtk::DrawingArea::default(),
+            speed_chart: gtk::DrawingArea::default(),
+            errors_chart: gtk::DrawingArea::default(),
+        };
+
+        // inject to view!
+        let best_chart = &model.best_chart;
+        let sessions_chart = &model.sessions_chart;
+        let speed_chart = &model.speed_chart;
+        let errors_chart = &model.errors_chart;
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _s: ComponentSender<Self>) {
+        match msg {
+            StatisticsPageInput::Show(days) => {
+                self.days = days;
+                self.redraw_charts();
+                self.hidden = false;
+            }
+            StatisticsPageInput::Hide => self.hidden = true,
+        }
+    }
+}
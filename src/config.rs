@@ -1,5 +1,18 @@
 pub const APP_ID: &str = "com.ilingu.gnopi";
+/// domain of the app's messages in the glib log
+pub const LOG_DOMAIN: &str = "gnopi";
 pub const VERSION: &str = "0.1";
 pub const PRELOADED_DIGITS: usize = 10;
+pub const CONTEXT_DIGITS: usize = 10;
 pub const PROGRESSBAR_FPS: usize = 30;
 pub const PROGRESSBAR_UPDATE_FREQUENCY: usize = 1000 / PROGRESSBAR_FPS;
+/// digits beyond the embedded million are computed, which gets slow quickly
+pub const MAX_DIGITS: usize = 2_000_000;
+pub const MAX_GAMMA_DIGITS: usize = 20_000;
+pub const MAX_LN2_DIGITS: usize = 100_000;
+pub const MAX_START_OFFSET: usize = 999_000;
+pub const DRILL_WIDEN_STEP: usize = 10;
+pub const SRS_CHUNK_SIZE: usize = 10;
+pub const SRS_CHUNKS_LEN: usize = 1_000_000 / SRS_CHUNK_SIZE;
+pub const SRS_NEW_CHUNKS: usize = 3;
+pub const SRS_MAX_REVIEWS: usize = 20;
@@ -0,0 +1,73 @@
+use num_bigint::BigUint;
+
+use super::{DigitSequence, DigitsError};
+use crate::app::storage::StorageError;
+
+/// the bases the digits can be played in
+pub const BASES: [u8; 5] = [2, 8, 10, 12, 16];
+/// extra source digits so that the truncation never reaches the converted ones
+const GUARD_DIGITS: usize = 10;
+
+/// number of base `to` digits known from `len` base `from` digits
+fn converted_len(len: usize, from: u8, to: u8) -> usize {
+    let known = len.saturating_sub(GUARD_DIGITS + 1);
+    (known as f64 * (from as f64).ln() / (to as f64).ln()) as usize
+}
+
+/// number of base `from` digits needed to know `len` base `to` digits
+fn source_len(len: usize, from: u8, to: u8) -> usize {
+    (len as f64 * (to as f64).ln() / (from as f64).ln()).ceil() as usize + GUARD_DIGITS
+}
+
+/// the first `len` digits after the point of 0.digits (written in base `from`) in base `to`
+pub fn convert(digits: &[u8], from: u8, to: u8, len: usize) -> Result<Vec<u8>, DigitsError> {
+    // the digits of a custom sequence can be edited by hand
+    let fraction = BigUint::from_radix_be(digits, from as u32).ok_or(StorageError::Corrupted)?;
+    let scaled =
+        fraction * BigUint::from(to).pow(len as u32) / BigUint::from(from).pow(digits.len() as u32);
+    let converted = scaled.to_radix_be(to as u32);
+
+    // the leading zeros are lost in the conversion
+    Ok(std::iter::repeat_n(0, len.saturating_sub(converted.len()))
+        .chain(converted)
+        .collect())
+}
+
+/// the first `len` digits of the sequence in base `base`, converted from its own base if needed
+pub fn digits_in_base<S: DigitSequence + ?Sized>(
+    sequence: &S,
+    len: usize,
+    base: u8,
+) -> Result<Vec<u8>, DigitsError> {
+    let from = sequence.base();
+    if from == base {
+        return sequence.digits(len);
+    }
+
+    let source = sequence.digits(source_len(len, from, base))?;
+    convert(&source, from, base, len)
+}
+
+/// number of digits of the sequence that can be played in base `base`
+pub fn max_len_in_base<S: DigitSequence + ?Sized>(sequence: &S, base: u8) -> usize {
+    match sequence.base() == base {
+        true => sequence.max_len(),
+        false => converted_len(sequence.max_len(), sequence.base(), base),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digits::{bbp, PI_DIGITS};
+
+    #[test]
+    fn hexadecimal_pi_agrees_with_digit_extraction() {
+        let len = converted_len(1000, 10, 16);
+        let hex = convert(&PI_DIGITS.unpack(0, 1000).unwrap(), 10, 16, len).unwrap();
+        assert_eq!(hex[..8], [2, 4, 3, 15, 6, 10, 8, 8]); // 3.243F6A88
+        for position in [0, 100, len - 1] {
+            assert_eq!(hex[position], bbp::pi_hex_digit(position));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/// base^exponent mod modulus
+fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let (mut result, mut base) = (1 % modulus as u128, base as u128 % modulus as u128);
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = result * base % modulus as u128;
+        }
+        base = base * base % modulus as u128;
+        exponent /= 2;
+    }
+    result as u64
+}
+
+/// fractional part of sum_k 16^(n-k) / (8k + j)
+fn series(j: u64, n: u64) -> f64 {
+    let mut sum = 0.0;
+    for k in 0..=n {
+        let denominator = 8 * k + j;
+        sum += mod_pow(16, n - k, denominator) as f64 / denominator as f64;
+        sum = sum.fract();
+    }
+    // the terms past n are below 1 and vanish quickly
+    for k in n + 1..n + 20 {
+        sum += 16f64.powi(-((k - n) as i32)) / (8 * k + j) as f64;
+    }
+    sum.fract()
+}
+
+/// hexadecimal digit of π at `position` after the point, without computing the previous ones (Bailey–Borwein–Plouffe formula)
+pub fn pi_hex_digit(position: usize) -> u8 {
+    let n = position as u64;
+    let fraction = 4.0 * series(1, n) - 2.0 * series(4, n) - series(5, n) - series(6, n);
+    (fraction.rem_euclid(1.0) * 16.0) as u8
+}
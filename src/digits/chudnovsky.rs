@@ -0,0 +1,52 @@
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// 640320^3 / 24
+const C3_OVER_24: u64 = 10_939_058_860_032_000;
+/// decimal digits gained by each term of the series
+const DIGITS_PER_TERM: f64 = 14.181647462725477;
+/// extra digits computed so that the rounding never reaches the returned ones
+const GUARD_DIGITS: usize = 10;
+
+/// Binary splitting of the Chudnovsky series over the terms [a, b), return (P, Q, T)
+fn split(a: u64, b: u64) -> (BigInt, BigInt, BigInt) {
+    if b - a == 1 {
+        let (p, q) = match a {
+            0 => (BigInt::from(1u8), BigInt::from(1u8)),
+            _ => (
+                BigInt::from(6 * a - 5) * (2 * a - 1) * (6 * a - 1),
+                BigInt::from(a) * a * a * C3_OVER_24,
+            ),
+        };
+        let mut t = &p * (13_591_409u64 + 545_140_134u64 * a);
+        if a % 2 == 1 {
+            t = -t;
+        }
+        return (p, q, t);
+    }
+
+    let m = (a + b) / 2;
+    let (p_am, q_am, t_am) = split(a, m);
+    let (p_mb, q_mb, t_mb) = split(m, b);
+    (&p_am * p_mb, q_am * &q_mb, q_mb * t_am + p_am * t_mb)
+}
+
+/// The first `len` decimal digits of π after the decimal point, one digit per byte
+pub fn pi_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+    let terms = (precision as f64 / DIGITS_PER_TERM) as u64 + 1;
+    let (_, q, t) = split(0, terms);
+
+    // π = 426880 * sqrt(10005) * Q / T, scaled by 10^precision to stay in integers
+    let one = BigUint::from(10u8).pow(precision as u32);
+    let sqrt_10005 = (BigUint::from(10_005u16) * &one * &one).sqrt();
+    let numerator = BigInt::from_biguint(Sign::Plus, sqrt_10005) * q * 426_880u32;
+    let pi = numerator / t;
+
+    // skip the leading "3"
+    pi.to_str_radix(10)
+        .bytes()
+        .skip(1)
+        .take(len)
+        .map(|c| c - b'0')
+        .collect()
+}
@@ -0,0 +1,127 @@
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// extra digits computed so that the rounding never reaches the returned ones
+const GUARD_DIGITS: usize = 20;
+
+/// `value` is a constant scaled by 10^precision, return its first `len` digits after the decimal point
+fn fraction_digits(value: &BigUint, precision: usize, len: usize) -> Vec<u8> {
+    let fraction = (value % BigUint::from(10u8).pow(precision as u32)).to_str_radix(10);
+    // the leading zeros of the fraction are lost in the conversion
+    let leading_zeros = precision - fraction.len();
+
+    std::iter::repeat_n(0, leading_zeros)
+        .chain(fraction.bytes().map(|c| c - b'0'))
+        .take(len)
+        .collect()
+}
+
+/// 10^precision * sqrt(n)
+fn scaled_sqrt(n: u32, precision: usize) -> BigUint {
+    let one = BigUint::from(10u8).pow(precision as u32);
+    (BigUint::from(n) * &one * &one).sqrt()
+}
+
+pub fn sqrt2_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+    fraction_digits(&scaled_sqrt(2, precision), precision, len)
+}
+
+/// φ = (1 + sqrt(5)) / 2
+pub fn golden_ratio_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+    let one = BigUint::from(10u8).pow(precision as u32);
+    let phi = (one + scaled_sqrt(5, precision)) / 2u8;
+    fraction_digits(&phi, precision, len)
+}
+
+/// Binary splitting of sum_{k=a+1}^{b} a!/k!, return (P, Q) with Q = (a+1)...b
+fn split_e(a: u64, b: u64) -> (BigUint, BigUint) {
+    if b - a == 1 {
+        return (BigUint::from(1u8), BigUint::from(b));
+    }
+
+    let m = (a + b) / 2;
+    let (p_am, q_am) = split_e(a, m);
+    let (p_mb, q_mb) = split_e(m, b);
+    (p_am * &q_mb + p_mb, q_am * q_mb)
+}
+
+/// e = sum 1/k!
+pub fn e_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+
+    // enough terms for the last one to be below 10^-precision
+    let mut terms = 1u64;
+    let mut log_factorial = 0.0;
+    while log_factorial < precision as f64 {
+        terms += 1;
+        log_factorial += (terms as f64).log10();
+    }
+
+    let (p, q) = split_e(0, terms);
+    let one = BigUint::from(10u8).pow(precision as u32);
+    let e = &one + p * &one / q;
+    fraction_digits(&e, precision, len)
+}
+
+/// 10^precision * ln(2), with ln(2) = 2 * atanh(1/3) = sum 2 / ((2k + 1) * 3^(2k + 1))
+fn scaled_ln2(precision: usize) -> BigInt {
+    let mut term = BigInt::from(2u8) * BigInt::from(10u8).pow(precision as u32) / 3u8;
+    let mut ln2 = BigInt::from(0u8);
+    let mut k = 0u64;
+    while term.sign() != Sign::NoSign {
+        ln2 += &term / (2 * k + 1);
+        term /= 9u8;
+        k += 1;
+    }
+    ln2
+}
+
+/// linear convergence with big divisions, so only practical for a hundred thousand digits
+pub fn ln2_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+    fraction_digits(scaled_ln2(precision).magnitude(), precision, len)
+}
+
+/// γ with the Brent–McMillan algorithm, quadratic so only practical for a few thousand digits
+pub fn euler_gamma_digits(len: usize) -> Vec<u8> {
+    let precision = len + GUARD_DIGITS;
+
+    // the error is about e^(-4n), n is a power of two so that ln(n) is a multiple of ln(2)
+    let min_n = (precision as f64 * std::f64::consts::LN_10 / 4.0).ceil() as u64 + 1;
+    let log2_n = 64 - (min_n - 1).leading_zeros();
+    let n = 1u64 << log2_n;
+    let n2 = n * n;
+
+    let mut b = BigInt::from(10u8).pow(precision as u32);
+    let mut a = -scaled_ln2(precision) * log2_n;
+    let mut u = a.clone();
+    let mut v = b.clone();
+    for k in 1..=4 * n {
+        b = b * n2 / (k * k);
+        a = (a * n2 / k + &b) / k;
+        u += &a;
+        v += &b;
+    }
+
+    let gamma = u * BigInt::from(10u8).pow(precision as u32) / v;
+    fraction_digits(gamma.magnitude(), precision, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits_of(text: &str) -> Vec<u8> {
+        text.bytes().map(|c| c - b'0').collect()
+    }
+
+    #[test]
+    fn constants_have_the_known_first_digits() {
+        assert_eq!(sqrt2_digits(20), digits_of("41421356237309504880"));
+        assert_eq!(golden_ratio_digits(20), digits_of("61803398874989484820"));
+        assert_eq!(e_digits(20), digits_of("71828182845904523536"));
+        assert_eq!(euler_gamma_digits(20), digits_of("57721566490153286060"));
+        assert_eq!(ln2_digits(20), digits_of("69314718055994530941"));
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{DigitSequence, DigitsError, Sequence};
+use crate::app::storage::{get_data_dir_path, get_data_file_path, StorageError};
+
+const NAME_FILE_NAME: &str = "name";
+const DIGITS_FILE_NAME: &str = "digits";
+const BASE_FILE_NAME: &str = "base";
+/// the custom sequences take the ids of the sequences from this one up to 255
+pub const MAX_CUSTOM_SEQUENCES: u8 = 128;
+
+/// Why a text file could not be imported as a sequence
+#[derive(Debug)]
+pub enum ImportError {
+    /// the file has a character that is not a digit of the base, or no digit at all
+    InvalidDigits,
+    /// all the custom sequence ids are taken
+    NoFreeId,
+    Storage(StorageError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::InvalidDigits => write!(f, "the file has no valid digits"),
+            ImportError::NoFreeId => write!(f, "there are too many imported sequences"),
+            ImportError::Storage(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<StorageError> for ImportError {
+    fn from(err: StorageError) -> Self {
+        ImportError::Storage(err)
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        ImportError::Storage(err.into())
+    }
+}
+
+/// path to a file of a custom sequence, without creating its directory
+fn get_custom_file_path(id: u8, file_name: &str) -> Result<PathBuf, StorageError> {
+    let sequence_dir = Sequence::Custom(id)
+        .data_dir()
+        .ok_or(StorageError::MissingDir)?;
+    Ok(get_data_dir_path()?.join(sequence_dir).join(file_name))
+}
+
+/// A sequence imported by the user, stored in its own data directory
+pub struct CustomSequence {
+    id: u8,
+    name: String,
+    len: usize,
+    base: u8,
+}
+
+impl CustomSequence {
+    pub fn load(id: u8) -> Self {
+        let name = get_custom_file_path(id, NAME_FILE_NAME)
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|_| format!("Sequence #{}", id + 1));
+        let len = get_custom_file_path(id, DIGITS_FILE_NAME)
+            .and_then(|path| Ok(fs::metadata(path)?))
+            .map(|metadata| metadata.len() as usize)
+            .unwrap_or_default();
+        // sequences imported before the other bases existed are decimal
+        let base = get_custom_file_path(id, BASE_FILE_NAME)
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|base| base.trim().parse::<u8>().ok())
+            .unwrap_or(10);
+
+        Self {
+            id,
+            name,
+            len,
+            base,
+        }
+    }
+}
+
+impl DigitSequence for CustomSequence {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn symbol(&self) -> &str {
+        &self.name
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        let mut digits = fs::read(get_custom_file_path(self.id, DIGITS_FILE_NAME)?)?;
+        if digits.len() < len || digits.iter().any(|d| *d >= self.base) {
+            return Err(StorageError::Corrupted.into()); // data corrupted
+        }
+        digits.truncate(len);
+        Ok(digits)
+    }
+
+    fn max_len(&self) -> usize {
+        self.len
+    }
+
+    fn base(&self) -> u8 {
+        self.base
+    }
+}
+
+/// every imported sequence, in import order
+pub fn list() -> Vec<Sequence> {
+    (0..MAX_CUSTOM_SEQUENCES)
+        .filter(|id| get_custom_file_path(*id, DIGITS_FILE_NAME).is_ok_and(|path| path.exists()))
+        .map(Sequence::Custom)
+        .collect()
+}
+
+/// the digits of a text file, whitespaces (line breaks, grouping spaces...) are ignored
+pub fn parse_digits(text: &str, radix: u32) -> Result<Vec<u8>, ()> {
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(radix).map(|d| d as u8).ok_or(()))
+        .collect::<Result<Vec<_>, ()>>()?;
+    match digits.is_empty() {
+        true => Err(()),
+        false => Ok(digits),
+    }
+}
+
+/// the first id without a sequence, None if they are all taken
+fn free_id() -> Result<Option<u8>, StorageError> {
+    for id in 0..MAX_CUSTOM_SEQUENCES {
+        if !get_custom_file_path(id, "")?.exists() {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
+
+/// the name, base and digits of an imported sequence
+pub fn read(id: u8) -> Result<(String, u8, Vec<u8>), StorageError> {
+    let sequence = CustomSequence::load(id);
+    let digits = fs::read(get_custom_file_path(id, DIGITS_FILE_NAME)?)?;
+    Ok((sequence.name, sequence.base, digits))
+}
+
+/// store the digits (in base `base`) in a new custom sequence
+fn create(name: &str, base: u8, digits: &[u8]) -> Result<Sequence, ImportError> {
+    if digits.is_empty() || digits.iter().any(|d| *d >= base) {
+        return Err(ImportError::InvalidDigits);
+    }
+    let id = free_id()?.ok_or(ImportError::NoFreeId)?;
+
+    let sequence = Sequence::Custom(id);
+    let write = |file_name: &str, content: &[u8]| -> Result<(), StorageError> {
+        fs::write(get_data_file_path(sequence, file_name)?, content)?;
+        Ok(())
+    };
+    write(NAME_FILE_NAME, name.as_bytes())?;
+    write(BASE_FILE_NAME, base.to_string().as_bytes())?;
+    write(DIGITS_FILE_NAME, digits)?;
+    Ok(sequence)
+}
+
+/// the imported sequence with this name, base and digits, created if there is none
+pub fn find_or_create(name: &str, base: u8, digits: &[u8]) -> Result<Sequence, ImportError> {
+    for sequence in list() {
+        let Sequence::Custom(id) = sequence else {
+            continue;
+        };
+        let (other_name, other_base, other_digits) = read(id)?;
+        if (other_name.as_str(), other_base, other_digits.as_slice()) == (name, base, digits) {
+            return Ok(sequence);
+        }
+    }
+    create(name, base, digits)
+}
+
+/// copy the digits (in base `base`) of a text file into a new custom sequence named after the file
+pub fn import(file_path: &Path, base: u8) -> Result<Sequence, ImportError> {
+    let text = fs::read_to_string(file_path)?;
+    let digits = parse_digits(&text, base as u32).map_err(|_| ImportError::InvalidDigits)?;
+    let name = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    create(&name, base, &digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespaces_are_ignored() {
+        assert_eq!(
+            parse_digits("1415 9265\n3589\t79\r\n", 10),
+            Ok(vec![1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9])
+        );
+        assert_eq!(
+            parse_digits("0010 0100\n", 2),
+            Ok(vec![0, 0, 1, 0, 0, 1, 0, 0])
+        );
+        assert_eq!(
+            parse_digits("243F 6a88", 16),
+            Ok(vec![2, 4, 3, 15, 6, 10, 8, 8])
+        );
+    }
+
+    #[test]
+    fn digits_must_be_in_the_base() {
+        assert!(parse_digits("0102", 2).is_err());
+        assert!(parse_digits("14159a", 10).is_err());
+        assert!(parse_digits("243g", 16).is_err());
+        assert!(parse_digits("3.14", 10).is_err());
+        assert!(parse_digits("", 10).is_err());
+        assert!(parse_digits(" \n\n", 10).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+pub mod base;
+pub mod bbp;
+pub mod chudnovsky;
+pub mod constants;
+pub mod custom;
+pub mod packed;
+pub mod search;
+pub mod sequence;
+
+use std::{
+    fmt, fs, io,
+    ops::Deref,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+pub use packed::PackedDigits;
+pub use sequence::{DigitSequence, Sequence};
+
+use crate::app::storage::StorageError;
+
+// include pi digits into the binary (1 million digits, packed by build.rs)
+pub const PI_DIGITS: PackedDigits = PackedDigits::new(
+    include_bytes!(concat!(env!("OUT_DIR"), "/pi_digits.bcd")),
+    1_000_000,
+);
+
+/// Digits of a sequence after the point, shared between the games (cheap to clone)
+#[derive(Clone, PartialEq)]
+pub struct Digits {
+    digits: Arc<[u8]>,
+    base: u8,
+}
+
+impl Digits {
+    pub fn new(digits: Vec<u8>, base: u8) -> Self {
+        Self {
+            digits: Arc::from(digits),
+            base,
+        }
+    }
+
+    /// the embedded π table, unpacked on the first call then shared
+    pub fn embedded() -> Self {
+        static EMBEDDED: OnceLock<Digits> = OnceLock::new();
+        EMBEDDED
+            .get_or_init(|| Self {
+                digits: Arc::from(PI_DIGITS.unpack(0, PI_DIGITS.len()).unwrap_or_default()),
+                base: 10,
+            })
+            .clone()
+    }
+
+    /// base the digits are written in
+    pub fn base(&self) -> u8 {
+        self.base
+    }
+}
+
+impl Deref for Digits {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.digits
+    }
+}
+
+impl fmt::Debug for Digits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // a million digits in the logs is of no help
+        write!(
+            f,
+            "Digits({} digits in base {})",
+            self.digits.len(),
+            self.base
+        )
+    }
+}
+
+/// Why the digits of a sequence could not be loaded
+#[derive(Debug)]
+pub enum DigitsError {
+    /// the sequence has no digit to play in this base
+    Empty,
+    /// the computed digits disagree with the known ones
+    Mismatch,
+    /// the stored digits could not be read, or are not digits of their base
+    Storage(StorageError),
+}
+
+impl fmt::Display for DigitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigitsError::Empty => write!(f, "there is no digit to play"),
+            DigitsError::Mismatch => write!(f, "the computed digits are wrong"),
+            DigitsError::Storage(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for DigitsError {}
+
+impl From<StorageError> for DigitsError {
+    fn from(err: StorageError) -> Self {
+        DigitsError::Storage(err)
+    }
+}
+
+impl From<io::Error> for DigitsError {
+    fn from(err: io::Error) -> Self {
+        DigitsError::Storage(err.into())
+    }
+}
+
+/// how a digit is displayed, letters are used past 9
+pub fn digit_char(digit: u8) -> char {
+    char::from_digit(digit as u32, 16)
+        .unwrap_or('?')
+        .to_ascii_uppercase()
+}
+
+/// Somewhere the digits of a sequence can be taken from
+pub trait DigitSource {
+    /// the first `len` digits after the decimal point
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError>;
+}
+
+/// The π table compiled into the binary
+pub struct EmbeddedSource;
+
+impl DigitSource for EmbeddedSource {
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        PI_DIGITS.unpack(0, len).ok_or(DigitsError::Empty)
+    }
+}
+
+/// Digits computed on demand, kept in the cache directory since it takes a while
+pub struct ComputedSource {
+    /// name of the cache file
+    pub name: &'static str,
+    pub compute: fn(usize) -> Result<Vec<u8>, DigitsError>,
+}
+
+impl ComputedSource {
+    /// return the path to the cached digits (and ensure that all the necessary directories exists)
+    fn get_cache_file_path(&self) -> Result<PathBuf, StorageError> {
+        let mut cache_path = dirs::cache_dir().ok_or(StorageError::MissingDir)?;
+
+        cache_path.push("gnopi");
+        fs::create_dir_all(&cache_path)?;
+
+        cache_path.push(format!("{}_digits", self.name));
+        Ok(cache_path)
+    }
+
+    fn read_cache(&self, len: usize) -> Result<Vec<u8>, StorageError> {
+        let mut cached = fs::read(self.get_cache_file_path()?)?;
+        if cached.len() < len || cached.iter().any(|d| *d > 9) {
+            return Err(StorageError::Corrupted); // not enough digits or data corrupted
+        }
+        cached.truncate(len);
+        Ok(cached)
+    }
+}
+
+impl DigitSource for ComputedSource {
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        if let Ok(cached) = self.read_cache(len) {
+            return Ok(cached);
+        }
+
+        let digits = (self.compute)(len)?;
+        // the digits are still usable if the cache can't be written
+        if let Ok(cache_file_path) = self.get_cache_file_path() {
+            let _ = fs::write(cache_file_path, &digits);
+        }
+        Ok(digits)
+    }
+}
+
+/// computed digits of π, refused if they disagree with the embedded table
+pub fn compute_checked(len: usize) -> Result<Vec<u8>, DigitsError> {
+    let digits = chudnovsky::pi_digits(len);
+    match first_mismatch(&digits) {
+        Some(_) => Err(DigitsError::Mismatch),
+        None => Ok(digits),
+    }
+}
+
+/// check digits against the embedded table, return the index of the first one that differs
+pub fn first_mismatch(digits: &[u8]) -> Option<usize> {
+    digits
+        .iter()
+        .zip(PI_DIGITS.iter())
+        .position(|(computed, embedded)| *computed != embedded)
+}
+
+/// the first `len` digits of the sequence in base `base` (or less if it can't have that many), computed if needed
+pub fn load_digits(sequence: Sequence, len: usize, base: u8) -> Result<Digits, DigitsError> {
+    let sequence = sequence.get();
+    match base::max_len_in_base(&*sequence, base).min(len) {
+        0 => Err(DigitsError::Empty),
+        len => sequence
+            .digits_in_base(len, base)
+            .map(|digits| Digits::new(digits, base)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_digits_are_unpacked_once() {
+        let (first, second) = (Digits::embedded(), Digits::embedded());
+        assert!(Arc::ptr_eq(&first.digits, &second.digits));
+        assert_eq!(first.len(), PI_DIGITS.len());
+    }
+
+    #[test]
+    fn computed_digits_match_the_embedded_table() {
+        assert_eq!(first_mismatch(&chudnovsky::pi_digits(20_000)), None);
+    }
+}
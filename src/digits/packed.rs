@@ -0,0 +1,72 @@
+/// Decimal digits stored two per byte (BCD, high nibble first), as packed by build.rs
+#[derive(Debug, Clone, Copy)]
+pub struct PackedDigits {
+    packed: &'static [u8],
+    len: usize,
+}
+
+impl PackedDigits {
+    pub const fn new(packed: &'static [u8], len: usize) -> Self {
+        assert!(len.div_ceil(2) <= packed.len());
+        Self { packed, len }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// the digit at `index`, panics if out of range like slice indexing
+    #[inline]
+    pub fn digit(&self, index: usize) -> u8 {
+        assert!(index < self.len, "digit index out of range");
+        let byte = self.packed[index / 2];
+        // even indices are in the high nibble
+        (byte >> (4 * (1 - index % 2))) & 0x0F
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<u8> {
+        (index < self.len).then(|| self.digit(index))
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = u8> + '_ {
+        (0..self.len).map(|index| self.digit(index))
+    }
+
+    /// the digits in `start..end` one per byte, None if out of range
+    pub fn unpack(&self, start: usize, end: usize) -> Option<Vec<u8>> {
+        (start <= end && end <= self.len).then(|| (start..end).map(|i| self.digit(i)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::digits::PI_DIGITS;
+
+    const UNPACKED_PI_DIGITS: &[u8] = include_bytes!("../../data/app/1m");
+
+    #[test]
+    fn unpacked_digits_match_the_original_file() {
+        assert_eq!(PI_DIGITS.len(), UNPACKED_PI_DIGITS.len());
+        assert!(PI_DIGITS.iter().eq(UNPACKED_PI_DIGITS.iter().copied()));
+        assert_eq!(
+            PI_DIGITS.unpack(0, PI_DIGITS.len()).as_deref(),
+            Some(UNPACKED_PI_DIGITS)
+        );
+    }
+
+    #[test]
+    fn out_of_range_digits_are_refused() {
+        assert_eq!(PI_DIGITS.get(PI_DIGITS.len()), None);
+        assert_eq!(
+            PI_DIGITS.get(PI_DIGITS.len() - 1),
+            Some(UNPACKED_PI_DIGITS[999_999])
+        );
+        assert_eq!(PI_DIGITS.unpack(10, PI_DIGITS.len() + 1), None);
+        assert_eq!(PI_DIGITS.unpack(5, 3), None);
+    }
+}
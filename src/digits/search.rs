@@ -0,0 +1,157 @@
+use super::Digits;
+
+/// longest indexed prefix, the table of k-grams gets too large past it
+const MAX_GRAM_LEN: u32 = 8;
+
+/// k-gram index of the digits: every position is bucketed by the k digits starting there
+pub struct SearchIndex {
+    digits: Digits,
+    gram_len: usize,
+    /// `positions[offsets[g]..offsets[g + 1]]` are the positions where the k-gram `g` starts, in ascending order
+    offsets: Vec<u32>,
+    positions: Vec<u32>,
+}
+
+impl SearchIndex {
+    pub fn new(digits: Digits) -> Self {
+        let base = digits.base() as usize;
+        // about one position per bucket
+        let mut gram_len = 1;
+        while gram_len < MAX_GRAM_LEN && base.pow(gram_len + 1) <= digits.len() {
+            gram_len += 1;
+        }
+        let gram_len = gram_len as usize;
+        let gram_count = base.pow(gram_len as u32);
+
+        // counting sort of the positions by k-gram
+        let grams = Self::grams(&digits, gram_len);
+        let mut offsets = vec![0u32; gram_count + 1];
+        for gram in grams.clone() {
+            offsets[gram + 1] += 1;
+        }
+        for g in 0..gram_count {
+            offsets[g + 1] += offsets[g];
+        }
+        let mut cursors = offsets.clone();
+        let mut positions = vec![0u32; offsets[gram_count] as usize];
+        for (position, gram) in grams.enumerate() {
+            positions[cursors[gram] as usize] = position as u32;
+            cursors[gram] += 1;
+        }
+
+        Self {
+            digits,
+            gram_len,
+            offsets,
+            positions,
+        }
+    }
+
+    /// the k-gram starting at every position that has k digits left
+    fn grams(digits: &Digits, gram_len: usize) -> impl Iterator<Item = usize> + Clone + '_ {
+        let base = digits.base() as usize;
+        let modulo = base.pow(gram_len as u32);
+        digits
+            .iter()
+            .scan(0, move |gram, digit| {
+                *gram = (*gram * base + *digit as usize) % modulo;
+                Some(*gram)
+            })
+            .skip(gram_len - 1)
+    }
+
+    /// value of the digits as a number written in the base of the sequence
+    fn gram_of(&self, digits: &[u8]) -> usize {
+        let base = self.digits.base() as usize;
+        digits
+            .iter()
+            .fold(0, |gram, digit| gram * base + *digit as usize)
+    }
+
+    pub fn digits(&self) -> &Digits {
+        &self.digits
+    }
+
+    /// every position where `query` occurs, in ascending order
+    pub fn find(&self, query: &[u8]) -> Vec<usize> {
+        let len = self.digits.len();
+        if query.is_empty() || query.len() > len {
+            return vec![];
+        }
+
+        let occurs_at = |position: usize| self.digits[position..].starts_with(query);
+        let bucket = |start: usize, end: usize| {
+            let (start, end) = (self.offsets[start], self.offsets[end]);
+            self.positions[start as usize..end as usize]
+                .iter()
+                .map(|position| *position as usize)
+        };
+
+        if query.len() >= self.gram_len {
+            // the first k digits give the bucket, the rest must be checked
+            let gram = self.gram_of(&query[..self.gram_len]);
+            return bucket(gram, gram + 1).filter(|p| occurs_at(*p)).collect();
+        }
+
+        // every k-gram starting with the query is a match, they are contiguous in the table
+        let scale = (self.digits.base() as usize).pow((self.gram_len - query.len()) as u32);
+        let gram = self.gram_of(query);
+        let mut occurrences: Vec<usize> = bucket(gram * scale, (gram + 1) * scale).collect();
+        // the last positions are too close to the end to have a k-gram
+        let tail_start = (len + 1).saturating_sub(self.gram_len);
+        occurrences.extend((tail_start..=len - query.len()).filter(|p| occurs_at(*p)));
+        occurrences.sort_unstable();
+        occurrences
+    }
+}
+
+/// digits typed in the search field, separators such as spaces, dashes or slashes are ignored
+pub fn parse_query(text: &str, base: u8) -> Result<Vec<u8>, ()> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_digit(base as u32).map(|d| d as u8).ok_or(()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_find(digits: &[u8], query: &[u8]) -> Vec<usize> {
+        (0..digits.len())
+            .filter(|p| digits[*p..].starts_with(query))
+            .collect()
+    }
+
+    #[test]
+    fn index_agrees_with_a_naive_search() {
+        let index = SearchIndex::new(Digits::new(
+            crate::digits::PI_DIGITS.unpack(0, 100_000).unwrap(),
+            10,
+        ));
+        for query in ["3", "14", "999999", "1415926535", "0000", "271828"] {
+            let query = parse_query(query, 10).unwrap();
+            assert_eq!(index.find(&query), naive_find(index.digits(), &query));
+        }
+    }
+
+    #[test]
+    fn matches_at_the_end_are_found() {
+        let digits = vec![1, 0, 1, 1, 0, 1, 1, 1];
+        let index = SearchIndex::new(Digits::new(digits.clone(), 2));
+        for query in [&[1][..], &[1, 1], &[0, 1, 1, 1], &[1, 1, 1, 1]] {
+            assert_eq!(index.find(query), naive_find(&digits, query));
+        }
+        assert_eq!(index.find(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn separators_are_ignored_in_queries() {
+        assert_eq!(
+            parse_query("14/03-15 92", 10),
+            Ok(vec![1, 4, 0, 3, 1, 5, 9, 2])
+        );
+        assert_eq!(parse_query("ff", 16), Ok(vec![15, 15]));
+        assert_eq!(parse_query("12a", 10), Err(()));
+    }
+}
@@ -0,0 +1,306 @@
+use super::{
+    base, bbp, compute_checked, constants,
+    custom::{CustomSequence, MAX_CUSTOM_SEQUENCES},
+    ComputedSource, DigitSource, DigitsError, EmbeddedSource, PI_DIGITS,
+};
+use crate::config;
+
+/// A sequence of digits to memorise, usually the decimals of a famous constant
+pub trait DigitSequence {
+    /// shown in the preferences
+    fn name(&self) -> &str;
+    /// shown in the game titles
+    fn symbol(&self) -> &str;
+    /// the first `len` digits after the point, in the base of the sequence
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError>;
+    /// number of digits that can be played, in the base of the sequence
+    fn max_len(&self) -> usize {
+        config::MAX_DIGITS
+    }
+    /// base the digits are written in
+    fn base(&self) -> u8 {
+        10
+    }
+    /// the first `len` digits after the point in another base
+    fn digits_in_base(&self, len: usize, base: u8) -> Result<Vec<u8>, DigitsError> {
+        base::digits_in_base(self, len, base)
+    }
+}
+
+pub struct Pi;
+
+impl DigitSequence for Pi {
+    fn name(&self) -> &str {
+        "π"
+    }
+
+    fn symbol(&self) -> &str {
+        "PI"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        if len <= PI_DIGITS.len() {
+            return EmbeddedSource.digits(len);
+        }
+        ComputedSource {
+            name: "pi",
+            compute: compute_checked,
+        }
+        .digits(len)
+    }
+
+    fn digits_in_base(&self, len: usize, base: u8) -> Result<Vec<u8>, DigitsError> {
+        let digits = base::digits_in_base(self, len, base)?;
+        // the hex digits can be extracted on their own, they must agree with the conversion
+        if base == 16 && len > 0 && digits.last() != Some(&bbp::pi_hex_digit(len - 1)) {
+            return Err(DigitsError::Mismatch);
+        }
+        Ok(digits)
+    }
+}
+
+/// τ = 2π
+pub struct Tau;
+
+impl DigitSequence for Tau {
+    fn name(&self) -> &str {
+        "τ (2π)"
+    }
+
+    fn symbol(&self) -> &str {
+        "TAU"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        // doubling a digit only carries when the next one is at least 5
+        let pi = Pi.digits(len + 1)?;
+        Ok(pi
+            .windows(2)
+            .map(|pair| (2 * pair[0] + (pair[1] >= 5) as u8) % 10)
+            .collect())
+    }
+
+    fn max_len(&self) -> usize {
+        Pi.max_len() - 1
+    }
+}
+
+pub struct E;
+
+impl DigitSequence for E {
+    fn name(&self) -> &str {
+        "e"
+    }
+
+    fn symbol(&self) -> &str {
+        "E"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        ComputedSource {
+            name: "e",
+            compute: |len| Ok(constants::e_digits(len)),
+        }
+        .digits(len)
+    }
+}
+
+pub struct GoldenRatio;
+
+impl DigitSequence for GoldenRatio {
+    fn name(&self) -> &str {
+        "φ (golden ratio)"
+    }
+
+    fn symbol(&self) -> &str {
+        "PHI"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        ComputedSource {
+            name: "phi",
+            compute: |len| Ok(constants::golden_ratio_digits(len)),
+        }
+        .digits(len)
+    }
+}
+
+pub struct Sqrt2;
+
+impl DigitSequence for Sqrt2 {
+    fn name(&self) -> &str {
+        "√2"
+    }
+
+    fn symbol(&self) -> &str {
+        "√2"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        ComputedSource {
+            name: "sqrt2",
+            compute: |len| Ok(constants::sqrt2_digits(len)),
+        }
+        .digits(len)
+    }
+}
+
+pub struct EulerGamma;
+
+impl DigitSequence for EulerGamma {
+    fn name(&self) -> &str {
+        "γ (Euler–Mascheroni)"
+    }
+
+    fn symbol(&self) -> &str {
+        "GAMMA"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        ComputedSource {
+            name: "gamma",
+            compute: |len| Ok(constants::euler_gamma_digits(len)),
+        }
+        .digits(len)
+    }
+
+    fn max_len(&self) -> usize {
+        config::MAX_GAMMA_DIGITS
+    }
+}
+
+pub struct Ln2;
+
+impl DigitSequence for Ln2 {
+    fn name(&self) -> &str {
+        "ln 2"
+    }
+
+    fn symbol(&self) -> &str {
+        "LN2"
+    }
+
+    fn digits(&self, len: usize) -> Result<Vec<u8>, DigitsError> {
+        ComputedSource {
+            name: "ln2",
+            compute: |len| Ok(constants::ln2_digits(len)),
+        }
+        .digits(len)
+    }
+
+    fn max_len(&self) -> usize {
+        config::MAX_LN2_DIGITS
+    }
+}
+
+/// The sequences the user can choose from
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Sequence {
+    #[default]
+    Pi,
+    Tau,
+    E,
+    GoldenRatio,
+    Sqrt2,
+    EulerGamma,
+    Ln2,
+    /// imported by the user
+    Custom(u8),
+}
+
+impl TryFrom<u8> for Sequence {
+    type Error = ();
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(Sequence::Pi),
+            1 => Ok(Sequence::Tau),
+            2 => Ok(Sequence::E),
+            3 => Ok(Sequence::GoldenRatio),
+            4 => Ok(Sequence::Sqrt2),
+            5 => Ok(Sequence::EulerGamma),
+            6 => Ok(Sequence::Ln2),
+            id if id >= MAX_CUSTOM_SEQUENCES => Ok(Sequence::Custom(id - MAX_CUSTOM_SEQUENCES)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Sequence> for u8 {
+    fn from(sequence: Sequence) -> Self {
+        match sequence {
+            Sequence::Pi => 0,
+            Sequence::Tau => 1,
+            Sequence::E => 2,
+            Sequence::GoldenRatio => 3,
+            Sequence::Sqrt2 => 4,
+            Sequence::EulerGamma => 5,
+            Sequence::Ln2 => 6,
+            Sequence::Custom(id) => MAX_CUSTOM_SEQUENCES + id,
+        }
+    }
+}
+
+impl Sequence {
+    /// the built-in sequences
+    pub const ALL: [Sequence; 7] = [
+        Sequence::Pi,
+        Sequence::Tau,
+        Sequence::E,
+        Sequence::GoldenRatio,
+        Sequence::Sqrt2,
+        Sequence::EulerGamma,
+        Sequence::Ln2,
+    ];
+
+    pub fn get(self) -> Box<dyn DigitSequence> {
+        match self {
+            Sequence::Pi => Box::new(Pi),
+            Sequence::Tau => Box::new(Tau),
+            Sequence::E => Box::new(E),
+            Sequence::GoldenRatio => Box::new(GoldenRatio),
+            Sequence::Sqrt2 => Box::new(Sqrt2),
+            Sequence::EulerGamma => Box::new(EulerGamma),
+            Sequence::Ln2 => Box::new(Ln2),
+            Sequence::Custom(id) => Box::new(CustomSequence::load(id)),
+        }
+    }
+
+    /// sub directory of the data directory holding its history and stats, π keeps the top level one
+    pub fn data_dir(self) -> Option<String> {
+        match self {
+            Sequence::Pi => None,
+            Sequence::Tau => Some("tau".to_string()),
+            Sequence::E => Some("e".to_string()),
+            Sequence::GoldenRatio => Some("phi".to_string()),
+            Sequence::Sqrt2 => Some("sqrt2".to_string()),
+            Sequence::EulerGamma => Some("gamma".to_string()),
+            Sequence::Ln2 => Some("ln2".to_string()),
+            Sequence::Custom(id) => Some(format!("custom_{id}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tau_is_twice_pi() {
+        assert_eq!(
+            Tau.digits(10).unwrap(),
+            vec![2, 8, 3, 1, 8, 5, 3, 0, 7, 1] // 6.2831853071
+        );
+    }
+
+    #[test]
+    fn ln2_can_be_picked() {
+        assert!(Sequence::ALL.contains(&Sequence::Ln2));
+        assert_eq!(
+            Sequence::try_from(u8::from(Sequence::Ln2)),
+            Ok(Sequence::Ln2)
+        );
+        assert_eq!(Sequence::Ln2.data_dir(), Some("ln2".to_string()));
+        assert_eq!(Sequence::Ln2.get().max_len(), config::MAX_LN2_DIGITS);
+    }
+}
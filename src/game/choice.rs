@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use super::random::Rng;
+
+/// number of candidates offered for every chunk
+pub const CANDIDATES: usize = 4;
+
+/// Bookkeeping of the choice mode, where the next chunk is picked among candidates
+#[derive(Debug, Clone)]
+pub struct Choice {
+    /// number of digits of the chunks
+    length: usize,
+    /// index in the digits of the first digit of the chunk to find
+    position: usize,
+    candidates: Vec<Vec<u8>>,
+    /// index in `candidates` of the right one
+    answer: usize,
+    /// candidates already picked for this chunk
+    wrong: Vec<usize>,
+    rng: Rng,
+    shown_at: Instant,
+    answered: usize,
+    total_reaction_time: Duration,
+}
+
+impl Choice {
+    pub fn new(length: usize, seed: u64) -> Self {
+        Self {
+            length: length.max(1),
+            position: 0,
+            candidates: vec![],
+            answer: 0,
+            wrong: vec![],
+            rng: Rng::new(seed),
+            shown_at: Instant::now(),
+            answered: 0,
+            total_reaction_time: Duration::ZERO,
+        }
+    }
+
+    /// offer candidates for the chunk of `digits` starting at `position`
+    pub fn ask(&mut self, digits: &[u8], position: usize, base: u8) {
+        let chunk = digits[position..(position + self.length).min(digits.len())].to_vec();
+
+        let mut candidates = vec![chunk.clone()];
+        // a chunk of one binary digit has no three distinct distractors
+        for _ in 0..100 {
+            if candidates.len() == CANDIDATES {
+                break;
+            }
+            let distractor = self.perturb(digits, position, &chunk, base);
+            if !candidates.contains(&distractor) {
+                candidates.push(distractor);
+            }
+        }
+        let answer = self.rng.below(candidates.len());
+        candidates.swap(0, answer);
+
+        self.position = position;
+        self.candidates = candidates;
+        self.answer = answer;
+        self.wrong.clear();
+        self.shown_at = Instant::now();
+    }
+
+    /// a plausible mistake: a digit changed, two digits swapped or the chunk shifted along the digits
+    fn perturb(&mut self, digits: &[u8], position: usize, chunk: &[u8], base: u8) -> Vec<u8> {
+        let mut distractor = chunk.to_vec();
+        let i = self.rng.below(chunk.len());
+        match self.rng.below(3) {
+            0 if chunk.len() > 1 => {
+                let j = (i + 1) % chunk.len();
+                distractor.swap(i, j);
+            }
+            1 => {
+                let shift = 1 + self.rng.below(2 * chunk.len());
+                let start = match self.rng.below(2) {
+                    0 => position.saturating_sub(shift),
+                    _ => position + shift,
+                };
+                if let Some(shifted) = digits.get(start..start + chunk.len()) {
+                    distractor.copy_from_slice(shifted);
+                }
+            }
+            _ => {
+                let delta = 1 + self.rng.below(base as usize - 1) as u8;
+                distractor[i] = (distractor[i] + delta) % base;
+            }
+        }
+        distractor
+    }
+
+    /// index in the digits of the first digit of the chunk to find
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn candidates(&self) -> &[Vec<u8>] {
+        &self.candidates
+    }
+
+    /// whether the candidate was already picked and was wrong
+    pub fn is_wrong(&self, candidate: usize) -> bool {
+        self.wrong.contains(&candidate)
+    }
+
+    /// to call when the user picks a candidate, return whether it is the right one
+    pub fn pick(&mut self, candidate: usize) -> bool {
+        if candidate != self.answer {
+            if !self.wrong.contains(&candidate) {
+                self.wrong.push(candidate);
+            }
+            return false;
+        }
+        self.answered += 1;
+        self.total_reaction_time += self.shown_at.elapsed();
+        true
+    }
+
+    /// mean time between a chunk being asked and the right candidate being picked
+    pub fn reaction_time(&self) -> Duration {
+        match self.answered {
+            0 => Duration::ZERO,
+            answered => self.total_reaction_time / answered as u32,
+        }
+    }
+}
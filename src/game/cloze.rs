@@ -0,0 +1,85 @@
+use super::random::Rng;
+
+/// Bookkeeping of the cloze mode, where a few digits of the window [start, end) are hidden and must be typed
+#[derive(Debug, Clone)]
+pub struct Cloze {
+    start: usize,
+    end: usize,
+    /// index in the digits of the hidden digits, in ascending order
+    gaps: Vec<usize>,
+    /// whether each gap already typed was right, in order
+    fills: Vec<bool>,
+}
+
+impl Cloze {
+    /// hide `density` percent of the digits of the window, at least one
+    pub fn new(start: usize, length: usize, density: u8, digits_len: usize, seed: u64) -> Self {
+        let end = (start + length.max(1)).min(digits_len);
+        let len = end - start;
+        let gap_count = (len * density.min(100) as usize)
+            .div_ceil(100)
+            .clamp(1, len);
+
+        // partial Fisher–Yates shuffle of the window
+        let mut rng = Rng::new(seed);
+        let mut positions = (start..end).collect::<Vec<_>>();
+        for i in 0..gap_count {
+            let j = i + rng.below(len - i);
+            positions.swap(i, j);
+        }
+        let mut gaps = positions[..gap_count].to_vec();
+        gaps.sort_unstable();
+
+        Self {
+            start,
+            end,
+            gaps,
+            fills: vec![],
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// exclusive end of the window
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn is_gap(&self, index: usize) -> bool {
+        self.gaps.binary_search(&index).is_ok()
+    }
+
+    /// index in the digits of the gap to type
+    pub fn current_gap(&self) -> Option<usize> {
+        self.gaps.get(self.filled()).cloned()
+    }
+
+    pub fn filled(&self) -> usize {
+        self.fills.len()
+    }
+
+    /// number of gaps typed with the right digit
+    pub fn right_fills(&self) -> usize {
+        self.fills.iter().filter(|right| **right).count()
+    }
+
+    pub fn gap_count(&self) -> usize {
+        self.gaps.len()
+    }
+
+    /// to call when the user typed the current gap, return the next one
+    pub fn fill_gap(&mut self, right: bool) -> Option<usize> {
+        if self.filled() < self.gaps.len() {
+            self.fills.push(right);
+        }
+        self.current_gap()
+    }
+
+    /// to call when the user erases the last typed gap, return it
+    pub fn unfill_gap(&mut self) -> Option<usize> {
+        self.fills.pop()?;
+        self.current_gap()
+    }
+}
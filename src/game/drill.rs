@@ -0,0 +1,91 @@
+use crate::config;
+
+/// Outcome of a complete run through the drill window
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LapResult {
+    /// no wrong digit during the lap
+    pub clean: bool,
+    /// number of clean laps in a row
+    pub streak: usize,
+    /// the window grew after this lap
+    pub widened: bool,
+}
+
+/// Bookkeeping of the drill mode, where the window [start, end) of the digits is typed again and again
+#[derive(Debug, Clone)]
+pub struct Drill {
+    start: usize,
+    end: usize,
+    /// number of clean laps in a row needed to widen the window, 0 to never widen
+    widen_after: u8,
+    /// the window can't go past the last digit
+    digits_len: usize,
+    lap: usize,
+    lap_errors: usize,
+    streak: usize,
+    best_streak: usize,
+}
+
+impl Drill {
+    pub fn new(start: usize, length: usize, widen_after: u8, digits_len: usize) -> Self {
+        Self {
+            start,
+            end: (start + length.max(1)).min(digits_len),
+            widen_after,
+            digits_len,
+            lap: 1,
+            lap_errors: 0,
+            streak: 0,
+            best_streak: 0,
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// exclusive end of the window
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// number of the current lap, starting at 1
+    pub fn lap(&self) -> usize {
+        self.lap
+    }
+
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    pub fn best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    pub fn add_mistake(&mut self) {
+        self.lap_errors += 1;
+    }
+
+    /// to call when the user typed the last digit of the window
+    pub fn complete_lap(&mut self) -> LapResult {
+        let clean = self.lap_errors == 0;
+        self.streak = if clean { self.streak + 1 } else { 0 };
+        self.best_streak = self.best_streak.max(self.streak);
+
+        let widened = self.widen_after > 0
+            && self.streak >= self.widen_after as usize
+            && self.end < self.digits_len;
+        if widened {
+            self.end = (self.end + config::DRILL_WIDEN_STEP).min(self.digits_len);
+            self.streak = 0;
+        }
+
+        self.lap += 1;
+        self.lap_errors = 0;
+        LapResult {
+            clean,
+            streak: self.streak,
+            widened,
+        }
+    }
+}
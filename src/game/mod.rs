@@ -0,0 +1,1341 @@
+pub mod choice;
+pub mod cloze;
+pub mod drill;
+pub mod quiz;
+pub mod random;
+pub mod reverse;
+pub mod srs;
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{config, digits::Digits};
+use choice::Choice;
+use cloze::Cloze;
+use drill::{Drill, LapResult};
+use quiz::{QuestionResult, Quiz, QuizScoring};
+use reverse::Reverse;
+use srs::{chunk_start, ChunkReview, Review};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AppMode {
+    Blind,
+    Learn,
+    InstantDeath,
+    Drill,
+    Review,
+    Quiz,
+    Cloze,
+    Reverse,
+    Choice,
+}
+
+impl TryFrom<u8> for AppMode {
+    type Error = ();
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(AppMode::Blind),
+            1 => Ok(AppMode::Learn),
+            2 => Ok(AppMode::InstantDeath),
+            3 => Ok(AppMode::Drill),
+            4 => Ok(AppMode::Review),
+            5 => Ok(AppMode::Quiz),
+            6 => Ok(AppMode::Cloze),
+            7 => Ok(AppMode::Reverse),
+            8 => Ok(AppMode::Choice),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum PiDigitState {
+    Right,
+    Wrong,
+    Placeholder,
+    /// digits before the start of the game, only shown as a reminder
+    Context,
+    /// digit to find in cloze, reverse and choice modes, not shown
+    Hidden,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameOverReason {
+    WrongDigit,
+    Timeout,
+    /// the user stopped the game
+    Finished,
+    /// there is nothing left to type (end of the review or of the digits)
+    Completed,
+}
+
+/// A wrong digit typed by the user
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mistake {
+    /// index in the digits
+    pub index: usize,
+    pub expected: u8,
+    pub typed: u8,
+}
+
+/// Everything there is to know about a finished game
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GameSummary {
+    pub mode: AppMode,
+    pub timeout: Option<Duration>,
+    pub reason: GameOverReason,
+    /// index in the digits where the game started
+    pub start_index: usize,
+    /// number of right digits typed before the first mistake of each board
+    pub digits_reached: usize,
+    /// index in the digits of the digit that ended the game
+    pub failing_index: usize,
+    pub expected: u8,
+    /// None when the game did not end on a wrong digit
+    pub typed: Option<u8>,
+    pub errors: usize,
+    pub elapsed: Duration,
+    /// most clean drill laps in a row, 0 in the other modes
+    pub best_streak: usize,
+    /// quiz points, 0 in the other modes
+    pub score: usize,
+    /// mean time to pick the right chunk, zero in the other modes
+    pub reaction_time: Duration,
+}
+
+impl GameSummary {
+    pub fn digits_per_minute(&self) -> f64 {
+        let minutes = self.elapsed.as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0.0;
+        }
+        self.digits_reached as f64 / minutes
+    }
+
+    /// what a personal best is about: the score in quiz mode, the digits reached otherwise
+    pub fn result(&self) -> usize {
+        match self.mode {
+            AppMode::Quiz => self.score,
+            _ => self.digits_reached,
+        }
+    }
+}
+
+/// How a game must be played, built from the user preferences
+#[derive(Debug, Clone)]
+pub struct GameSettings {
+    pub mode: AppMode,
+    /// the digits to type
+    pub digits: Digits,
+    pub timeout: Option<Duration>,
+    /// index in the digits of the first digit to type
+    pub start_index: usize,
+    /// number of digits in the drill window
+    pub drill_length: usize,
+    /// number of clean laps in a row needed to widen the drill window, 0 to never widen
+    pub drill_widen_after: u8,
+    /// chunks to type in review mode
+    pub review_queue: VecDeque<usize>,
+    /// the quiz questions are picked in the first `quiz_range` digits
+    pub quiz_range: usize,
+    /// number of digits asked per quiz question
+    pub quiz_length: usize,
+    pub quiz_questions: usize,
+    pub quiz_scoring: QuizScoring,
+    /// number of digits in the cloze window
+    pub cloze_length: usize,
+    /// percentage of the cloze window that is hidden
+    pub cloze_density: u8,
+    /// number of digits typed backwards in reverse mode
+    pub reverse_length: usize,
+    /// number of digits of the chunks to pick in choice mode
+    pub choice_length: usize,
+    /// number of digits shown before the chunk to pick in choice mode
+    pub choice_context: usize,
+    /// for the modes picking positions at random
+    pub seed: u64,
+}
+
+/// What the user can do to a running game
+#[derive(Debug, Copy, Clone)]
+pub enum GameInput {
+    Digit(u8),
+    /// pick a candidate in choice mode
+    Choose(usize),
+    Backspace,
+    /// stop the game and get its summary
+    Finish,
+    /// time elapsed since the last tick
+    Tick(Duration),
+}
+
+/// State changes emitted by the game, the view only has to render them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameEvent {
+    /// append a digit at the end of the board
+    Push(u8, PiDigitState),
+    /// change the digit at the given board index
+    Update(usize, u8, PiDigitState),
+    /// remove the last digit of the board
+    Pop,
+    /// remove all the digits of the board
+    Clear,
+    /// the timeout must restart from zero
+    RestartTimer,
+    /// the user typed a wrong digit and the game goes on
+    Mistake(Mistake),
+    /// the user typed the whole drill window, the board restarts from its beginning
+    LapCompleted(LapResult),
+    /// the user typed a whole chunk in review mode, the board moves to the next chunk
+    ChunkReviewed(ChunkReview),
+    /// the user typed a whole answer in quiz mode, the board moves to the next question
+    QuestionAnswered(QuestionResult),
+    GameOver(GameSummary),
+}
+
+/// What is needed to resume a game: it is played again from its settings
+#[derive(Debug, Clone)]
+pub struct SavedGame {
+    pub settings: GameSettings,
+    /// the digits typed, candidates picked and digits removed, in order
+    pub inputs: Vec<GameInput>,
+    /// time played before the game was saved
+    pub elapsed: Duration,
+}
+
+/// Headless π game: holds all the rules of the app modes, without any gtk code
+#[derive(Debug, Clone)]
+pub struct GameSession {
+    /// kept to save the game
+    settings: GameSettings,
+    /// the inputs that changed the game, replayed to resume it
+    inputs: Vec<GameInput>,
+    mode: AppMode,
+    digits: Digits,
+    timeout: Option<Duration>,
+    start_index: usize,
+    /// index in the digits of the first digit to type on the current board
+    board_start: usize,
+    curr_pi_index: usize,
+    /// number of context digits displayed before the board start
+    context_len: usize,
+    /// digits reached on the previous boards
+    completed_digits: usize,
+    /// index in the digits of the first wrong digit left on the current board
+    first_mistake: Option<usize>,
+    /// number of digits currently displayed on the board
+    board_len: usize,
+    timeout_progress: f64,
+    timer_running: bool,
+    errors: usize,
+    /// set when the first digit is typed
+    started_at: Option<Instant>,
+    /// time played before the game was resumed
+    previous_elapsed: Duration,
+    over: Option<GameOverReason>,
+    /// only in drill mode
+    drill: Option<Drill>,
+    /// only in review mode
+    review: Option<Review>,
+    /// only in quiz mode
+    quiz: Option<Quiz>,
+    /// only in cloze mode
+    cloze: Option<Cloze>,
+    /// only in reverse mode
+    reverse: Option<Reverse>,
+    /// only in choice mode
+    choice: Option<Choice>,
+}
+
+impl GameSession {
+    pub fn new(settings: GameSettings) -> Self {
+        let saved_settings = settings.clone();
+        let mode = settings.mode;
+        let digits = settings.digits;
+        let start_index = settings.start_index.min(digits.len() - 1);
+        let drill = (mode == AppMode::Drill).then(|| {
+            Drill::new(
+                start_index,
+                settings.drill_length,
+                settings.drill_widen_after,
+                digits.len(),
+            )
+        });
+        let mut review_queue = settings.review_queue;
+        review_queue.retain(|chunk| chunk_start(*chunk) < digits.len());
+        let review = (mode == AppMode::Review).then(|| Review::new(review_queue));
+        let quiz = (mode == AppMode::Quiz).then(|| {
+            Quiz::new(
+                settings.quiz_range,
+                settings.quiz_length,
+                settings.quiz_questions,
+                settings.quiz_scoring,
+                digits.len(),
+                settings.seed,
+            )
+        });
+        let cloze = (mode == AppMode::Cloze).then(|| {
+            Cloze::new(
+                start_index,
+                settings.cloze_length,
+                settings.cloze_density,
+                digits.len(),
+                settings.seed,
+            )
+        });
+        let reverse = (mode == AppMode::Reverse)
+            .then(|| Reverse::new(start_index, settings.reverse_length, digits.len()));
+        let choice =
+            (mode == AppMode::Choice).then(|| Choice::new(settings.choice_length, settings.seed));
+
+        let board_start = match (
+            review.as_ref().and_then(|r| r.current_chunk()),
+            quiz.as_ref().and_then(|q| q.current_question()),
+        ) {
+            (Some(chunk), _) => chunk_start(chunk),
+            (_, Some(position)) => position,
+            _ => start_index,
+        };
+
+        Self {
+            settings: saved_settings,
+            inputs: vec![],
+            mode,
+            digits,
+            timeout: settings.timeout,
+            start_index,
+            board_start,
+            curr_pi_index: board_start,
+            context_len: Self::context_len(mode, board_start, settings.choice_context),
+            completed_digits: 0,
+            first_mistake: None,
+            board_len: 0,
+            timeout_progress: 0.0,
+            timer_running: false,
+            errors: 0,
+            started_at: None,
+            previous_elapsed: Duration::ZERO,
+            // no chunk is due, there is no digit to type
+            over: review
+                .as_ref()
+                .is_some_and(|review| review.current_chunk().is_none())
+                .then_some(GameOverReason::Completed),
+            drill,
+            review,
+            quiz,
+            cloze,
+            reverse,
+            choice,
+        }
+    }
+
+    /// number of context digits displayed before a board starting at `board_start`
+    fn context_len(mode: AppMode, board_start: usize, choice_context: usize) -> usize {
+        match mode {
+            // the quiz is about finding the digits without the ones before
+            AppMode::Quiz => 0,
+            // the digits before are the last ones to type
+            AppMode::Reverse => 0,
+            AppMode::Choice => board_start.min(choice_context),
+            _ => board_start.min(config::CONTEXT_DIGITS),
+        }
+    }
+
+    pub fn drill(&self) -> Option<&Drill> {
+        self.drill.as_ref()
+    }
+
+    pub fn review(&self) -> Option<&Review> {
+        self.review.as_ref()
+    }
+
+    pub fn quiz(&self) -> Option<&Quiz> {
+        self.quiz.as_ref()
+    }
+
+    pub fn cloze(&self) -> Option<&Cloze> {
+        self.cloze.as_ref()
+    }
+
+    pub fn reverse(&self) -> Option<&Reverse> {
+        self.reverse.as_ref()
+    }
+
+    pub fn choice(&self) -> Option<&Choice> {
+        self.choice.as_ref()
+    }
+
+    /// board index of the next digit to type
+    pub fn cursor(&self) -> usize {
+        self.board_index(self.curr_pi_index)
+    }
+
+    fn board_index(&self, pi_index: usize) -> usize {
+        pi_index - self.board_start + self.context_len
+    }
+
+    /// between 0.0 and 1.0, the game is over when it reaches 1.0
+    pub fn timeout_progress(&self) -> f64 {
+        self.timeout_progress
+    }
+
+    /// whether the user typed at least one digit
+    pub fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over.is_some()
+    }
+
+    /// time played since the first digit
+    pub fn elapsed(&self) -> Duration {
+        self.previous_elapsed
+            + self
+                .started_at
+                .map(|started_at| started_at.elapsed())
+                .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> SavedGame {
+        SavedGame {
+            settings: self.settings.clone(),
+            inputs: self.inputs.clone(),
+            elapsed: self.elapsed(),
+        }
+    }
+
+    /// replay a saved game, returning the events needed to draw its board
+    pub fn resume(saved: SavedGame) -> Result<(Self, Vec<GameEvent>), ()> {
+        let mut game = Self::new(saved.settings);
+        let mut events = game.start();
+        for input in saved.inputs {
+            events.extend(game.handle(input));
+        }
+        if game.is_over() {
+            return Err(()); // a saved game is never over, it does not fit these digits
+        }
+        game.previous_elapsed = saved.elapsed;
+        if game.started_at.is_some() {
+            game.started_at = Some(Instant::now());
+        }
+
+        // the mistakes, laps and reviews were already handled before the game was saved
+        events.retain(|event| {
+            matches!(
+                event,
+                GameEvent::Push(..)
+                    | GameEvent::Update(..)
+                    | GameEvent::Pop
+                    | GameEvent::Clear
+                    | GameEvent::RestartTimer
+            )
+        });
+        Ok((game, events))
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+        if timeout.is_none() {
+            self.timer_running = false;
+            self.timeout_progress = 0.0;
+        }
+    }
+
+    /// the events needed to draw the initial board
+    pub fn start(&mut self) -> Vec<GameEvent> {
+        if self.is_over() {
+            return vec![];
+        }
+        self.curr_pi_index = self.board_start;
+        let context = &self.digits[self.board_start - self.context_len..self.board_start];
+        let mut events = context
+            .iter()
+            .map(|d| GameEvent::Push(*d, PiDigitState::Context))
+            .collect::<Vec<_>>();
+        self.board_len = self.context_len;
+
+        if self.mode == AppMode::Learn {
+            let preloaded = &self.digits[self.board_start..];
+            let preloaded = &preloaded[..config::PRELOADED_DIGITS.min(preloaded.len())];
+            events.extend(
+                preloaded
+                    .iter()
+                    .map(|d| GameEvent::Push(*d, PiDigitState::Placeholder)),
+            );
+            self.board_len += preloaded.len();
+        }
+        if let Some(cloze) = &self.cloze {
+            events.extend((cloze.start()..cloze.end()).map(|index| {
+                let state = match cloze.is_gap(index) {
+                    true => PiDigitState::Hidden,
+                    false => PiDigitState::Placeholder,
+                };
+                GameEvent::Push(self.digits[index], state)
+            }));
+            self.board_len += cloze.end() - cloze.start();
+            self.curr_pi_index = cloze.current_gap().unwrap_or(cloze.end());
+        }
+        if let Some(reverse) = &self.reverse {
+            events.extend(
+                self.digits[reverse.start()..reverse.end()]
+                    .iter()
+                    .map(|d| GameEvent::Push(*d, PiDigitState::Hidden)),
+            );
+            self.board_len += reverse.end() - reverse.start();
+            self.curr_pi_index = reverse.current().unwrap_or(reverse.start());
+        }
+        if let Some(choice) = self.choice.as_mut() {
+            choice.ask(&self.digits, self.board_start, self.digits.base());
+            let chunk_len = choice.candidates()[0].len();
+            events.extend(
+                (0..chunk_len).map(|i| {
+                    GameEvent::Push(self.digits[self.board_start + i], PiDigitState::Hidden)
+                }),
+            );
+            self.board_len += chunk_len;
+        }
+        events
+    }
+
+    pub fn handle(&mut self, input: GameInput) -> Vec<GameEvent> {
+        if self.is_over() {
+            return vec![];
+        }
+        if let GameInput::Digit(_) | GameInput::Choose(_) | GameInput::Backspace = input {
+            self.inputs.push(input);
+        }
+        match input {
+            // the digits are picked, not typed
+            GameInput::Digit(_) if self.mode == AppMode::Choice => vec![],
+            GameInput::Digit(digit) => self.add_digit(digit),
+            GameInput::Choose(candidate) => self.choose(candidate),
+            GameInput::Backspace => self.remove_last_digit(),
+            GameInput::Finish => self.game_over(GameOverReason::Finished, None),
+            GameInput::Tick(elapsed) => self.tick(elapsed),
+        }
+    }
+
+    fn game_over(&mut self, reason: GameOverReason, typed: Option<u8>) -> Vec<GameEvent> {
+        self.over = Some(reason);
+        self.timer_running = false;
+
+        let digits_reached = match (&self.cloze, &self.reverse) {
+            // the wrong digits move on too, they are not reached
+            (Some(cloze), _) => cloze.right_fills(),
+            (_, Some(reverse)) => reverse.right_digits(),
+            _ => self.completed_digits + self.board_reached(),
+        };
+        let summary = GameSummary {
+            mode: self.mode,
+            timeout: self.timeout,
+            reason,
+            start_index: self.start_index,
+            digits_reached,
+            failing_index: self.curr_pi_index,
+            // nothing to expect after the last digit
+            expected: self
+                .digits
+                .get(self.curr_pi_index)
+                .cloned()
+                .unwrap_or_default(),
+            typed,
+            errors: self.errors,
+            elapsed: self.elapsed(),
+            best_streak: self.drill().map(|d| d.best_streak()).unwrap_or_default(),
+            score: self.quiz().map(|q| q.score()).unwrap_or_default(),
+            reaction_time: self.choice().map(|c| c.reaction_time()).unwrap_or_default(),
+        };
+        vec![GameEvent::GameOver(summary)]
+    }
+
+    fn tick(&mut self, elapsed: Duration) -> Vec<GameEvent> {
+        let timeout = match self.timeout {
+            Some(timeout) if self.timer_running => timeout,
+            _ => return vec![],
+        };
+
+        self.timeout_progress += elapsed.as_secs_f64() / timeout.as_secs_f64();
+        if self.timeout_progress >= 1.0 {
+            return self.game_over(GameOverReason::Timeout, None);
+        }
+        vec![]
+    }
+
+    fn add_digit(&mut self, digit: u8) -> Vec<GameEvent> {
+        self.started_at.get_or_insert_with(Instant::now);
+        let state = if digit == self.digits[self.curr_pi_index] {
+            PiDigitState::Right
+        } else {
+            PiDigitState::Wrong
+        };
+
+        if state == PiDigitState::Wrong {
+            self.errors += 1;
+            self.first_mistake.get_or_insert(self.curr_pi_index);
+            if self.mode == AppMode::InstantDeath {
+                return self.game_over(GameOverReason::WrongDigit, Some(digit));
+            }
+        }
+
+        let mut events = vec![];
+        if state == PiDigitState::Wrong {
+            if let Some(drill) = self.drill.as_mut() {
+                drill.add_mistake();
+            }
+            if let Some(review) = self.review.as_mut() {
+                review.add_mistake();
+            }
+            if let Some(quiz) = self.quiz.as_mut() {
+                quiz.add_mistake();
+            }
+            events.push(GameEvent::Mistake(Mistake {
+                index: self.curr_pi_index,
+                expected: self.digits[self.curr_pi_index],
+                typed: digit,
+            }));
+        }
+        if state == PiDigitState::Right && self.timeout.is_some() {
+            self.timeout_progress = 0.0;
+            self.timer_running = true;
+            events.push(GameEvent::RestartTimer);
+        }
+
+        match self.mode {
+            AppMode::Blind
+            | AppMode::InstantDeath
+            | AppMode::Drill
+            | AppMode::Review
+            | AppMode::Quiz => {
+                events.push(GameEvent::Push(digit, state));
+                self.board_len += 1;
+            }
+            AppMode::Cloze | AppMode::Reverse => {
+                events.push(GameEvent::Update(self.cursor(), digit, state))
+            }
+            // the digits are picked with `choose`
+            AppMode::Choice => return vec![],
+            AppMode::Learn => {
+                events.push(GameEvent::Update(self.cursor(), digit, state));
+
+                // add next visible digit
+                let next_index = self.curr_pi_index + config::PRELOADED_DIGITS;
+                if next_index < self.digits.len() && self.board_index(next_index) >= self.board_len
+                {
+                    events.push(GameEvent::Push(
+                        self.digits[next_index],
+                        PiDigitState::Placeholder,
+                    ));
+                    self.board_len += 1;
+                }
+            }
+        };
+
+        if let Some(cloze) = self.cloze.as_mut() {
+            // jump to the next gap, the visible digits are not typed
+            match cloze.fill_gap(state == PiDigitState::Right) {
+                Some(gap) => self.curr_pi_index = gap,
+                None => {
+                    self.curr_pi_index = cloze.end();
+                    events.extend(self.game_over(GameOverReason::Completed, None));
+                }
+            }
+            return events;
+        }
+        if let Some(reverse) = self.reverse.as_mut() {
+            // right to left, down to the start of the window
+            match reverse.type_digit(state == PiDigitState::Right) {
+                Some(index) => self.curr_pi_index = index,
+                None => events.extend(self.game_over(GameOverReason::Completed, None)),
+            }
+            return events;
+        }
+        self.curr_pi_index += 1;
+
+        if let Some(drill) = self.drill.as_mut() {
+            if self.curr_pi_index == drill.end() {
+                // restart from the beginning of the window
+                events.push(GameEvent::LapCompleted(drill.complete_lap()));
+                events.extend(self.next_board(self.board_start));
+            }
+        }
+        if let Some(review) = self.review.as_mut() {
+            // the last chunk ends with the digits
+            if self.curr_pi_index == self.board_start + config::SRS_CHUNK_SIZE
+                || self.curr_pi_index == self.digits.len()
+            {
+                events.extend(review.complete_chunk().map(GameEvent::ChunkReviewed));
+                match review.current_chunk() {
+                    Some(chunk) => events.extend(self.next_board(chunk_start(chunk))),
+                    None => events.extend(self.game_over(GameOverReason::Completed, None)),
+                }
+            }
+        }
+        if let Some(quiz) = self.quiz.as_mut() {
+            if self.curr_pi_index == self.board_start + quiz.length() {
+                events.extend(quiz.complete_question().map(GameEvent::QuestionAnswered));
+                match quiz.current_question() {
+                    Some(position) => events.extend(self.next_board(position)),
+                    None => events.extend(self.game_over(GameOverReason::Completed, None)),
+                }
+            }
+        }
+        if self.curr_pi_index == self.digits.len() && !self.is_over() {
+            // end of the data, no more digit to check against
+            events.extend(self.game_over(GameOverReason::Completed, None));
+        }
+        events
+    }
+
+    fn choose(&mut self, candidate: usize) -> Vec<GameEvent> {
+        let choice = match self.choice.as_mut() {
+            Some(choice) if candidate < choice.candidates().len() => choice,
+            _ => return vec![],
+        };
+        if choice.is_wrong(candidate) {
+            return vec![]; // its key can be pressed again, its button is disabled
+        }
+        self.started_at.get_or_insert_with(Instant::now);
+        let picked = choice.candidates()[candidate].clone();
+
+        let mut events = vec![];
+        if !choice.pick(candidate) {
+            // show the picked chunk, no digit was typed so it is not in the mistakes
+            self.errors += 1;
+            for (i, picked) in picked.iter().enumerate() {
+                let index = self.board_start + i;
+                let state = match *picked == self.digits[index] {
+                    true => PiDigitState::Right,
+                    false => PiDigitState::Wrong,
+                };
+                events.push(GameEvent::Update(self.board_index(index), *picked, state));
+            }
+            return events;
+        }
+
+        if self.timeout.is_some() {
+            self.timeout_progress = 0.0;
+            self.timer_running = true;
+            events.push(GameEvent::RestartTimer);
+        }
+        self.curr_pi_index = self.board_start + picked.len();
+        match self.curr_pi_index == self.digits.len() {
+            true => events.extend(self.game_over(GameOverReason::Completed, None)),
+            false => events.extend(self.next_board(self.curr_pi_index)),
+        }
+        events
+    }
+
+    /// clear the board and start typing again from `board_start`
+    fn next_board(&mut self, board_start: usize) -> Vec<GameEvent> {
+        self.completed_digits += self.board_reached();
+        self.first_mistake = None;
+        self.board_start = board_start;
+        self.context_len = Self::context_len(self.mode, board_start, self.settings.choice_context);
+
+        let mut events = vec![GameEvent::Clear];
+        events.extend(self.start());
+        events
+    }
+
+    /// the digits typed on the current board up to the first mistake, the next ones are not known
+    fn board_reached(&self) -> usize {
+        self.first_mistake.unwrap_or(self.curr_pi_index) - self.board_start
+    }
+
+    fn remove_last_digit(&mut self) -> Vec<GameEvent> {
+        if self
+            .first_mistake
+            .is_some_and(|index| index + 1 >= self.curr_pi_index)
+        {
+            // the only wrong digit left is removed
+            self.first_mistake = None;
+        }
+        match self.mode {
+            AppMode::Cloze | AppMode::Reverse => {
+                let previous = match (self.cloze.as_mut(), self.reverse.as_mut()) {
+                    (Some(cloze), _) => cloze.unfill_gap(),
+                    (_, Some(reverse)) => reverse.untype_digit(),
+                    _ => None,
+                };
+                let previous = match previous {
+                    Some(previous) => previous,
+                    None => return vec![],
+                };
+                self.curr_pi_index = previous;
+                vec![GameEvent::Update(
+                    self.cursor(),
+                    self.digits[previous],
+                    PiDigitState::Hidden,
+                )]
+            }
+            _ if self.curr_pi_index == self.board_start => vec![],
+            AppMode::Blind | AppMode::Drill | AppMode::Review | AppMode::Quiz => {
+                self.curr_pi_index -= 1;
+                self.board_len -= 1;
+                vec![GameEvent::Pop]
+            }
+            AppMode::Learn => {
+                // remove last digit of the user and add right one
+                self.curr_pi_index -= 1;
+                vec![GameEvent::Update(
+                    self.cursor(),
+                    self.digits[self.curr_pi_index],
+                    PiDigitState::Placeholder,
+                )]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digits::PI_DIGITS;
+
+    fn settings(mode: AppMode, start_index: usize) -> GameSettings {
+        GameSettings {
+            mode,
+            digits: Digits::embedded(),
+            timeout: None,
+            start_index,
+            drill_length: 20,
+            drill_widen_after: 0,
+            review_queue: VecDeque::new(),
+            quiz_range: 100,
+            quiz_length: 10,
+            quiz_questions: 3,
+            quiz_scoring: QuizScoring::PerDigit,
+            cloze_length: 50,
+            cloze_density: 20,
+            reverse_length: 20,
+            choice_length: 4,
+            choice_context: 3,
+            seed: 42,
+        }
+    }
+
+    /// the digits displayed after the events
+    fn draw(events: &[GameEvent]) -> Vec<(u8, PiDigitState)> {
+        let mut board = vec![];
+        for event in events {
+            match *event {
+                GameEvent::Push(digit, state) => board.push((digit, state)),
+                GameEvent::Update(index, digit, state) => board[index] = (digit, state),
+                GameEvent::Pop => _ = board.pop(),
+                GameEvent::Clear => board.clear(),
+                _ => {}
+            }
+        }
+        board
+    }
+
+    /// type the right digits until the game is over
+    fn type_until_over(game: &mut GameSession) -> Vec<GameEvent> {
+        let mut events = game.start();
+        while !game.is_over() {
+            let digit = PI_DIGITS.digit(game.curr_pi_index);
+            events.extend(game.handle(GameInput::Digit(digit)));
+        }
+        events
+    }
+
+    /// a digit other than the one at `index`
+    fn wrong_digit(index: usize) -> u8 {
+        (PI_DIGITS.digit(index) + 1) % 10
+    }
+
+    #[test]
+    fn blind_and_learn_check_every_digit() {
+        for mode in [AppMode::Blind, AppMode::Learn] {
+            let mut game = GameSession::new(settings(mode, 0));
+            game.start();
+
+            let right = PI_DIGITS.digit(0);
+            let events = game.handle(GameInput::Digit(right));
+            assert!(
+                events.contains(&GameEvent::Push(right, PiDigitState::Right))
+                    || events.contains(&GameEvent::Update(0, right, PiDigitState::Right)),
+                "{mode:?}"
+            );
+
+            let wrong = wrong_digit(1);
+            let events = game.handle(GameInput::Digit(wrong));
+            assert!(events.contains(&GameEvent::Mistake(Mistake {
+                index: 1,
+                expected: PI_DIGITS.digit(1),
+                typed: wrong,
+            })));
+            assert!(
+                events.contains(&GameEvent::Push(wrong, PiDigitState::Wrong))
+                    || events.contains(&GameEvent::Update(1, wrong, PiDigitState::Wrong)),
+                "{mode:?}"
+            );
+            assert!(!game.is_over());
+            assert_eq!(game.cursor(), 2);
+        }
+    }
+
+    #[test]
+    fn instant_death_ends_on_the_first_wrong_digit() {
+        let mut game = GameSession::new(settings(AppMode::InstantDeath, 0));
+        game.start();
+        game.handle(GameInput::Digit(PI_DIGITS.digit(0)));
+        let wrong = wrong_digit(1);
+
+        match game.handle(GameInput::Digit(wrong)).as_slice() {
+            [GameEvent::GameOver(summary)] => {
+                assert_eq!(summary.reason, GameOverReason::WrongDigit);
+                assert_eq!(summary.digits_reached, 1);
+                assert_eq!(summary.failing_index, 1);
+                assert_eq!(summary.expected, PI_DIGITS.digit(1));
+                assert_eq!(summary.typed, Some(wrong));
+            }
+            events => panic!("instant death went on: {events:?}"),
+        }
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn backspace_removes_the_last_digit() {
+        let mut game = GameSession::new(settings(AppMode::Blind, 0));
+        game.start();
+        assert!(game.handle(GameInput::Backspace).is_empty());
+        game.handle(GameInput::Digit(PI_DIGITS.digit(0)));
+        game.handle(GameInput::Digit(wrong_digit(1)));
+        assert_eq!(game.handle(GameInput::Backspace), vec![GameEvent::Pop]);
+        assert_eq!(game.cursor(), 1);
+
+        let mut game = GameSession::new(settings(AppMode::Learn, 0));
+        game.start();
+        game.handle(GameInput::Digit(wrong_digit(0)));
+        assert_eq!(
+            game.handle(GameInput::Backspace),
+            vec![GameEvent::Update(
+                0,
+                PI_DIGITS.digit(0),
+                PiDigitState::Placeholder
+            )]
+        );
+        assert_eq!(game.cursor(), 0);
+    }
+
+    #[test]
+    fn digits_reached_stop_at_the_first_mistake_left() {
+        let mut game = GameSession::new(settings(AppMode::Blind, 0));
+        game.start();
+        game.handle(GameInput::Digit(PI_DIGITS.digit(0)));
+        game.handle(GameInput::Digit(wrong_digit(1)));
+        for index in 2..10 {
+            game.handle(GameInput::Digit(PI_DIGITS.digit(index)));
+        }
+        match game.clone().handle(GameInput::Finish).as_slice() {
+            [GameEvent::GameOver(summary)] => assert_eq!(summary.digits_reached, 1),
+            events => panic!("finish did not end the game: {events:?}"),
+        }
+
+        // fixing the mistake counts the digits again
+        for _ in 1..10 {
+            game.handle(GameInput::Backspace);
+        }
+        for index in 1..10 {
+            game.handle(GameInput::Digit(PI_DIGITS.digit(index)));
+        }
+        match game.handle(GameInput::Finish).as_slice() {
+            [GameEvent::GameOver(summary)] => assert_eq!(summary.digits_reached, 10),
+            events => panic!("finish did not end the game: {events:?}"),
+        }
+    }
+
+    #[test]
+    fn timeout_ends_the_game() {
+        let mut settings = settings(AppMode::Blind, 0);
+        settings.timeout = Some(Duration::from_secs(2));
+        let mut game = GameSession::new(settings);
+        game.start();
+        // the timer starts with the first right digit
+        assert!(game
+            .handle(GameInput::Tick(Duration::from_secs(5)))
+            .is_empty());
+
+        let events = game.handle(GameInput::Digit(PI_DIGITS.digit(0)));
+        assert!(events.contains(&GameEvent::RestartTimer));
+        assert!(game
+            .handle(GameInput::Tick(Duration::from_secs(1)))
+            .is_empty());
+        assert_eq!(game.timeout_progress(), 0.5);
+
+        match game
+            .handle(GameInput::Tick(Duration::from_secs(1)))
+            .as_slice()
+        {
+            [GameEvent::GameOver(summary)] => {
+                assert_eq!(summary.reason, GameOverReason::Timeout);
+                assert_eq!(summary.digits_reached, 1);
+            }
+            events => panic!("the timeout did not end the game: {events:?}"),
+        }
+    }
+
+    #[test]
+    fn finish_ends_the_game() {
+        let mut game = GameSession::new(settings(AppMode::Blind, 0));
+        game.start();
+        for index in 0..3 {
+            game.handle(GameInput::Digit(PI_DIGITS.digit(index)));
+        }
+
+        match game.handle(GameInput::Finish).as_slice() {
+            [GameEvent::GameOver(summary)] => {
+                assert_eq!(summary.reason, GameOverReason::Finished);
+                assert_eq!(summary.digits_reached, 3);
+                assert_eq!(summary.failing_index, 3);
+                assert_eq!(summary.typed, None);
+            }
+            events => panic!("finish did not end the game: {events:?}"),
+        }
+        assert!(game.handle(GameInput::Digit(PI_DIGITS.digit(3))).is_empty());
+    }
+
+    #[test]
+    fn every_mode_completes_at_the_end_of_the_digits() {
+        for mode in [AppMode::Blind, AppMode::Learn, AppMode::InstantDeath] {
+            let mut game = GameSession::new(settings(mode, PI_DIGITS.len() - 25));
+            let events = type_until_over(&mut game);
+
+            match events.last() {
+                Some(GameEvent::GameOver(summary)) => {
+                    assert_eq!(summary.reason, GameOverReason::Completed);
+                    assert_eq!(summary.digits_reached, 25);
+                    assert_eq!(summary.failing_index, PI_DIGITS.len());
+                    assert_eq!(summary.errors, 0);
+                }
+                event => panic!("{mode:?} did not end with a game over: {event:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn last_digit_can_be_wrong() {
+        let mut game = GameSession::new(settings(AppMode::Blind, PI_DIGITS.len() - 1));
+        game.start();
+        let wrong_digit = (PI_DIGITS.digit(999_999) + 1) % 10;
+        let events = game.handle(GameInput::Digit(wrong_digit));
+
+        assert!(events.contains(&GameEvent::Push(wrong_digit, PiDigitState::Wrong)));
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn input_after_the_end_is_ignored() {
+        let mut game = GameSession::new(settings(AppMode::Learn, PI_DIGITS.len() - 1));
+        type_until_over(&mut game);
+
+        assert!(game.handle(GameInput::Digit(0)).is_empty());
+        assert!(game.handle(GameInput::Backspace).is_empty());
+        assert!(game.handle(GameInput::Finish).is_empty());
+    }
+
+    #[test]
+    fn learn_does_not_preload_past_the_end() {
+        let mut game = GameSession::new(settings(AppMode::Learn, PI_DIGITS.len() - 3));
+        let placeholders = game
+            .start()
+            .into_iter()
+            .filter(|e| matches!(e, GameEvent::Push(_, PiDigitState::Placeholder)))
+            .count();
+
+        assert_eq!(placeholders, 3);
+    }
+
+    #[test]
+    fn start_offset_is_clamped_to_the_last_digit() {
+        let mut game = GameSession::new(settings(AppMode::Blind, PI_DIGITS.len() + 10));
+        let events = type_until_over(&mut game);
+
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::GameOver(GameSummary {
+                digits_reached: 1,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn drill_window_loops_at_the_end_of_the_digits() {
+        let mut settings = settings(AppMode::Drill, PI_DIGITS.len() - 5);
+        settings.drill_widen_after = 1;
+        let mut game = GameSession::new(settings);
+        game.start();
+
+        for lap in 1..=3 {
+            let mut events = vec![];
+            for digit in PI_DIGITS.iter().skip(PI_DIGITS.len() - 5) {
+                events.extend(game.handle(GameInput::Digit(digit)));
+            }
+            // can not be widened past the end
+            assert!(events.contains(&GameEvent::LapCompleted(LapResult {
+                clean: true,
+                streak: lap,
+                widened: false,
+            })));
+            assert!(!game.is_over());
+        }
+        assert_eq!(game.drill().map(|d| d.end()), Some(PI_DIGITS.len()));
+    }
+
+    #[test]
+    fn review_of_the_last_chunk_completes() {
+        let mut settings = settings(AppMode::Review, 0);
+        settings.review_queue = VecDeque::from([config::SRS_CHUNKS_LEN - 1]);
+        let mut game = GameSession::new(settings);
+        let events = type_until_over(&mut game);
+
+        assert!(events.contains(&GameEvent::ChunkReviewed(ChunkReview {
+            chunk: config::SRS_CHUNKS_LEN - 1,
+            grade: 5,
+        })));
+        assert_eq!(game.curr_pi_index, PI_DIGITS.len());
+    }
+
+    #[test]
+    fn review_grades_the_short_last_chunk() {
+        let mut settings = settings(AppMode::Review, 0);
+        settings.digits = Digits::new(PI_DIGITS.unpack(0, 25).unwrap(), 10);
+        settings.review_queue = VecDeque::from([2, 0]);
+        let mut game = GameSession::new(settings);
+        let events = type_until_over(&mut game);
+
+        let reviews = events
+            .iter()
+            .filter_map(|e| match e {
+                GameEvent::ChunkReviewed(review) => Some(review.chunk),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reviews, vec![2, 0]);
+    }
+
+    #[test]
+    fn review_without_due_chunks_refuses_the_digits() {
+        let mut game = GameSession::new(settings(AppMode::Review, 0));
+        assert!(game.is_over());
+        assert!(game.start().is_empty());
+        for digit in 0..10 {
+            assert!(game.handle(GameInput::Digit(digit)).is_empty());
+        }
+        assert!(game.handle(GameInput::Finish).is_empty());
+    }
+
+    #[test]
+    fn quiz_asks_every_question_without_context() {
+        let mut settings = settings(AppMode::Quiz, 0);
+        settings.quiz_scoring = QuizScoring::PerQuestion;
+        let mut game = GameSession::new(settings);
+        assert!(game.start().is_empty());
+
+        let mut answers = vec![];
+        // one wrong digit in the first answer
+        let wrong_digit = (PI_DIGITS.digit(game.curr_pi_index) + 1) % 10;
+        game.handle(GameInput::Digit(wrong_digit));
+        game.handle(GameInput::Backspace);
+        while !game.is_over() {
+            assert!(game.curr_pi_index < 100);
+            let digit = PI_DIGITS.digit(game.curr_pi_index);
+            for event in game.handle(GameInput::Digit(digit)) {
+                match event {
+                    GameEvent::QuestionAnswered(result) => answers.push(result.points),
+                    GameEvent::Push(_, PiDigitState::Context) => panic!("context in a quiz"),
+                    _ => {}
+                }
+            }
+        }
+
+        assert_eq!(answers, vec![0, 1, 1]);
+        assert_eq!(game.quiz().map(|q| q.score()), Some(2));
+        assert_eq!(game.quiz().map(|q| q.max_score()), Some(3));
+    }
+
+    #[test]
+    fn cloze_only_asks_for_the_gaps() {
+        let mut game = GameSession::new(settings(AppMode::Cloze, 100));
+        let hidden = game
+            .start()
+            .into_iter()
+            .filter(|e| matches!(e, GameEvent::Push(_, PiDigitState::Hidden)))
+            .count();
+        assert_eq!(hidden, 10);
+
+        let first_gap = game.curr_pi_index;
+        let wrong_digit = (PI_DIGITS.digit(first_gap) + 1) % 10;
+        game.handle(GameInput::Digit(wrong_digit));
+        assert!(game.curr_pi_index > first_gap);
+        assert_eq!(
+            game.handle(GameInput::Backspace),
+            vec![GameEvent::Update(
+                game.cursor(),
+                PI_DIGITS.digit(first_gap),
+                PiDigitState::Hidden
+            )]
+        );
+        assert_eq!(game.curr_pi_index, first_gap);
+
+        match type_until_over(&mut game).last() {
+            Some(GameEvent::GameOver(summary)) => {
+                assert_eq!(summary.reason, GameOverReason::Completed);
+                assert_eq!(summary.digits_reached, 10);
+                assert_eq!(summary.errors, 1);
+            }
+            event => panic!("cloze did not end with a game over: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn wrong_gaps_are_not_reached() {
+        let mut game = GameSession::new(settings(AppMode::Cloze, 100));
+        let mut events = game.start();
+        while !game.is_over() {
+            let digit = wrong_digit(game.curr_pi_index);
+            events.extend(game.handle(GameInput::Digit(digit)));
+        }
+
+        match events.last() {
+            Some(GameEvent::GameOver(summary)) => {
+                assert_eq!(summary.digits_reached, 0);
+                assert_eq!(summary.errors, 10);
+            }
+            event => panic!("cloze did not end with a game over: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn reverse_fills_the_window_from_right_to_left() {
+        let mut game = GameSession::new(settings(AppMode::Reverse, 100));
+        let events = game.start();
+        assert_eq!(events.len(), 20);
+        assert_eq!(game.curr_pi_index, 119);
+
+        let mut cursors = vec![];
+        let events = type_until_over(&mut game);
+        for event in &events {
+            if let GameEvent::Update(cursor, _, state) = event {
+                assert_eq!(*state, PiDigitState::Right);
+                cursors.push(*cursor);
+            }
+        }
+
+        assert_eq!(cursors, (0..20).rev().collect::<Vec<_>>());
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::GameOver(GameSummary {
+                reason: GameOverReason::Completed,
+                digits_reached: 20,
+                failing_index: 100,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn wrong_reverse_digits_are_not_reached() {
+        let mut game = GameSession::new(settings(AppMode::Reverse, 100));
+        let mut events = game.start();
+        // only the last digit of the window is right
+        events.extend(game.handle(GameInput::Digit(PI_DIGITS.digit(119))));
+        while !game.is_over() {
+            let digit = wrong_digit(game.curr_pi_index);
+            events.extend(game.handle(GameInput::Digit(digit)));
+        }
+
+        match events.last() {
+            Some(GameEvent::GameOver(summary)) => {
+                assert_eq!(summary.digits_reached, 1);
+                assert_eq!(summary.errors, 19);
+            }
+            event => panic!("reverse did not end with a game over: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn choice_moves_on_with_the_right_chunk() {
+        let mut game = GameSession::new(settings(AppMode::Choice, 100));
+        let events = game.start();
+        let context = events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Push(_, PiDigitState::Context)))
+            .count();
+        assert_eq!(context, 3);
+        let hidden = events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Push(_, PiDigitState::Hidden)))
+            .count();
+        assert_eq!(hidden, 4);
+
+        let chunk = PI_DIGITS.unpack(100, 104).unwrap();
+        let candidates = game.choice().unwrap().candidates().to_vec();
+        assert_eq!(candidates.len(), choice::CANDIDATES);
+        assert_eq!(candidates.iter().filter(|c| **c == chunk).count(), 1);
+        for (i, candidate) in candidates.iter().enumerate() {
+            assert!(!candidates[i + 1..].contains(candidate));
+        }
+
+        let answer = candidates.iter().position(|c| *c == chunk).unwrap();
+        let wrong = (answer + 1) % candidates.len();
+        let events = game.handle(GameInput::Choose(wrong));
+        assert!(events.iter().all(|e| !matches!(e, GameEvent::Mistake(_))));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, GameEvent::Update(_, _, PiDigitState::Wrong))));
+        assert!(game.choice().unwrap().is_wrong(wrong));
+        assert!(game.handle(GameInput::Choose(wrong)).is_empty());
+        assert!(game.handle(GameInput::Digit(chunk[0])).is_empty());
+
+        let events = game.handle(GameInput::Choose(answer));
+        assert_eq!(events.first(), Some(&GameEvent::Clear));
+        assert_eq!(game.choice().map(|c| c.position()), Some(104));
+
+        match game.handle(GameInput::Finish).last() {
+            Some(GameEvent::GameOver(summary)) => {
+                assert_eq!(summary.digits_reached, 4);
+                assert_eq!(summary.errors, 1);
+            }
+            event => panic!("choice did not end with a game over: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn resumed_game_has_the_same_board() {
+        for mode in [
+            AppMode::Blind,
+            AppMode::Drill,
+            AppMode::Cloze,
+            AppMode::Choice,
+        ] {
+            let mut game = GameSession::new(settings(mode, 30));
+            let mut events = game.start();
+            for _ in 0..5 {
+                let input = match game.choice() {
+                    Some(choice) => GameInput::Choose(choice.candidates().len() - 1),
+                    None => GameInput::Digit(PI_DIGITS.digit(game.curr_pi_index)),
+                };
+                events.extend(game.handle(input));
+            }
+            events.extend(game.handle(GameInput::Digit(0)));
+            events.extend(game.handle(GameInput::Backspace));
+
+            let saved = game.save();
+            let (resumed, resumed_events) = GameSession::resume(saved.clone()).unwrap();
+            assert_eq!(draw(&resumed_events), draw(&events), "{mode:?}");
+            assert_eq!(resumed.cursor(), game.cursor());
+            assert!(resumed_events
+                .iter()
+                .all(|e| !matches!(e, GameEvent::Mistake(_) | GameEvent::LapCompleted(_))));
+            assert!(resumed.elapsed() >= saved.elapsed);
+        }
+    }
+}
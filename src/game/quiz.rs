@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+use super::random::Rng;
+
+/// How the answers of the quiz are scored
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QuizScoring {
+    /// a point per right digit
+    PerDigit,
+    /// a point per question answered without a wrong digit
+    PerQuestion,
+}
+
+impl TryFrom<u8> for QuizScoring {
+    type Error = ();
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(QuizScoring::PerDigit),
+            1 => Ok(QuizScoring::PerQuestion),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Outcome of an answered question
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QuestionResult {
+    /// index in the digits of the first digit asked
+    pub position: usize,
+    pub errors: usize,
+    pub points: usize,
+}
+
+/// Bookkeeping of the quiz mode, where the digits at random positions are asked without context
+#[derive(Debug, Clone)]
+pub struct Quiz {
+    /// index in the digits of the first digit of every question left
+    questions: VecDeque<usize>,
+    /// number of digits asked per question
+    length: usize,
+    scoring: QuizScoring,
+    total: usize,
+    question_errors: usize,
+    score: usize,
+}
+
+impl Quiz {
+    /// `questions` positions picked in the first `range` digits
+    pub fn new(
+        range: usize,
+        length: usize,
+        questions: usize,
+        scoring: QuizScoring,
+        digits_len: usize,
+        seed: u64,
+    ) -> Self {
+        let range = range.clamp(1, digits_len);
+        let length = length.clamp(1, range);
+        let mut rng = Rng::new(seed);
+        let questions = (0..questions.max(1))
+            .map(|_| rng.below(range - length + 1))
+            .collect::<VecDeque<_>>();
+
+        Self {
+            total: questions.len(),
+            questions,
+            length,
+            scoring,
+            question_errors: 0,
+            score: 0,
+        }
+    }
+
+    /// index in the digits of the first digit of the current question
+    pub fn current_question(&self) -> Option<usize> {
+        self.questions.front().cloned()
+    }
+
+    /// number of the current question, starting at 1
+    pub fn question_number(&self) -> usize {
+        self.total - self.questions.len() + 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// number of digits asked per question
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    pub fn max_score(&self) -> usize {
+        match self.scoring {
+            QuizScoring::PerDigit => self.total * self.length,
+            QuizScoring::PerQuestion => self.total,
+        }
+    }
+
+    pub fn add_mistake(&mut self) {
+        self.question_errors += 1;
+    }
+
+    /// to call when the user typed the last digit of the current question
+    pub fn complete_question(&mut self) -> Option<QuestionResult> {
+        let position = self.questions.pop_front()?;
+        let errors = self.question_errors.min(self.length);
+        let points = match self.scoring {
+            QuizScoring::PerDigit => self.length - errors,
+            QuizScoring::PerQuestion => (errors == 0) as usize,
+        };
+        self.score += points;
+        self.question_errors = 0;
+        Some(QuestionResult {
+            position,
+            errors,
+            points,
+        })
+    }
+}
@@ -0,0 +1,55 @@
+/// Bookkeeping of the reverse mode, where the window [start, end) is typed from its last digit down to its first
+#[derive(Debug, Clone)]
+pub struct Reverse {
+    start: usize,
+    end: usize,
+    /// whether each digit already typed was right, in order
+    typed: Vec<bool>,
+}
+
+impl Reverse {
+    pub fn new(start: usize, length: usize, digits_len: usize) -> Self {
+        Self {
+            start,
+            end: (start + length.max(1)).min(digits_len),
+            typed: vec![],
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// exclusive end of the window
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn typed(&self) -> usize {
+        self.typed.len()
+    }
+
+    /// number of digits typed right
+    pub fn right_digits(&self) -> usize {
+        self.typed.iter().filter(|right| **right).count()
+    }
+
+    /// index in the digits of the digit to type
+    pub fn current(&self) -> Option<usize> {
+        (self.typed() < self.end - self.start).then(|| self.end - 1 - self.typed())
+    }
+
+    /// to call when the user typed the current digit, return the next one
+    pub fn type_digit(&mut self, right: bool) -> Option<usize> {
+        if self.typed() < self.end - self.start {
+            self.typed.push(right);
+        }
+        self.current()
+    }
+
+    /// to call when the user erases the last typed digit, return it
+    pub fn untype_digit(&mut self) -> Option<usize> {
+        self.typed.pop()?;
+        self.current()
+    }
+}
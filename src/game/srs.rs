@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::config;
+
+/// Review state of one chunk of `config::SRS_CHUNK_SIZE` digits (SM-2 algorithm)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChunkCard {
+    /// the chunk covers digits[chunk * SRS_CHUNK_SIZE..(chunk + 1) * SRS_CHUNK_SIZE]
+    pub chunk: usize,
+    pub ease: f64,
+    /// in days
+    pub interval: u32,
+    /// number of successful reviews in a row
+    pub repetitions: u32,
+    /// in days since the unix epoch
+    pub due: u64,
+}
+
+impl ChunkCard {
+    pub fn new(chunk: usize) -> Self {
+        Self {
+            chunk,
+            ease: 2.5,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+        }
+    }
+
+    /// update the card with a grade between 0 (blackout) and 5 (perfect recall)
+    pub fn grade(&mut self, grade: u8, today: u64) {
+        let grade = grade.min(5);
+        if grade >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval = 1;
+        }
+
+        let penalty = (5 - grade) as f64;
+        self.ease = (self.ease + 0.1 - penalty * (0.08 + penalty * 0.02)).max(1.3);
+        self.due = today + self.interval as u64;
+    }
+}
+
+/// Grade of a chunk typed with `errors` wrong digits
+pub fn grade_from_errors(errors: usize) -> u8 {
+    match errors {
+        0 => 5,
+        1 => 4,
+        2 => 3,
+        3 => 2,
+        _ => 1,
+    }
+}
+
+/// All the chunks the user already reviewed
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    cards: BTreeMap<usize, ChunkCard>,
+}
+
+impl Scheduler {
+    pub fn new(cards: impl IntoIterator<Item = ChunkCard>) -> Self {
+        Self {
+            cards: cards.into_iter().map(|card| (card.chunk, card)).collect(),
+        }
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &ChunkCard> {
+        self.cards.values()
+    }
+
+    /// chunks of the `digits_len` digits to review today: the due ones, most overdue first, then a few never seen ones
+    pub fn due_chunks(&self, today: u64, digits_len: usize) -> VecDeque<usize> {
+        // the last chunk can be shorter
+        let chunks_len = digits_len
+            .div_ceil(config::SRS_CHUNK_SIZE)
+            .min(config::SRS_CHUNKS_LEN);
+        let mut due = self
+            .cards
+            .values()
+            .filter(|card| card.due <= today && card.chunk < chunks_len)
+            .collect::<Vec<_>>();
+        due.sort_by_key(|card| (card.due, card.chunk));
+
+        let new_chunks = (0..chunks_len)
+            .filter(|chunk| !self.cards.contains_key(chunk))
+            .take(config::SRS_NEW_CHUNKS);
+
+        due.into_iter()
+            .map(|card| card.chunk)
+            .chain(new_chunks)
+            .take(config::SRS_MAX_REVIEWS)
+            .collect()
+    }
+
+    pub fn grade(&mut self, chunk: usize, grade: u8, today: u64) -> ChunkCard {
+        let card = self
+            .cards
+            .entry(chunk)
+            .or_insert_with(|| ChunkCard::new(chunk));
+        card.grade(grade, today);
+        *card
+    }
+}
+
+/// Outcome of a reviewed chunk
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChunkReview {
+    pub chunk: usize,
+    pub grade: u8,
+}
+
+/// Bookkeeping of the review mode, where the due chunks are typed one after the other
+#[derive(Debug, Clone)]
+pub struct Review {
+    queue: VecDeque<usize>,
+    chunk_errors: usize,
+}
+
+impl Review {
+    pub fn new(queue: VecDeque<usize>) -> Self {
+        Self {
+            queue,
+            chunk_errors: 0,
+        }
+    }
+
+    /// chunk being reviewed
+    pub fn current_chunk(&self) -> Option<usize> {
+        self.queue.front().cloned()
+    }
+
+    /// number of chunks left to review, the current one included
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn add_mistake(&mut self) {
+        self.chunk_errors += 1;
+    }
+
+    /// to call when the user typed the last digit of the current chunk
+    pub fn complete_chunk(&mut self) -> Option<ChunkReview> {
+        let chunk = self.queue.pop_front()?;
+        let grade = grade_from_errors(self.chunk_errors);
+        self.chunk_errors = 0;
+        Some(ChunkReview { chunk, grade })
+    }
+}
+
+/// index in the digits of the first digit of the chunk
+pub fn chunk_start(chunk: usize) -> usize {
+    chunk * config::SRS_CHUNK_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_grow_with_the_ease() {
+        let mut card = ChunkCard::new(0);
+        let mut intervals = vec![];
+        for today in 0..4 {
+            card.grade(5, today);
+            intervals.push(card.interval);
+        }
+
+        // the ease grows by 0.1 with each perfect recall
+        assert_eq!(intervals, vec![1, 6, 16, 45]);
+        assert_eq!(card.repetitions, 4);
+        assert!((card.ease - 2.9).abs() < 1e-9);
+        assert_eq!(card.due, 3 + 45);
+    }
+
+    #[test]
+    fn ease_does_not_drop_below_the_floor() {
+        let mut card = ChunkCard::new(0);
+        for today in 0..10 {
+            card.grade(3, today);
+        }
+        assert_eq!(card.ease, 1.3);
+        assert_eq!(card.repetitions, 10);
+    }
+
+    #[test]
+    fn failing_grade_resets_the_card() {
+        let mut card = ChunkCard::new(0);
+        card.grade(5, 0);
+        card.grade(4, 1);
+        assert_eq!((card.repetitions, card.interval), (2, 6));
+
+        card.grade(2, 7);
+        assert_eq!((card.repetitions, card.interval, card.due), (0, 1, 8));
+        assert!(card.ease < 2.5);
+        card.grade(5, 8);
+        assert_eq!(card.interval, 1);
+    }
+
+    #[test]
+    fn most_overdue_chunks_come_first() {
+        let card = |chunk, due| ChunkCard {
+            due,
+            ..ChunkCard::new(chunk)
+        };
+        let scheduler = Scheduler::new([card(0, 12), card(1, 10), card(2, 30), card(3, 10)]);
+        let due = scheduler.due_chunks(20, 1000);
+
+        // then the first never seen chunks
+        let mut expected = vec![1, 3, 0];
+        expected.extend((4..).take(config::SRS_NEW_CHUNKS));
+        expected.truncate(config::SRS_MAX_REVIEWS);
+        assert_eq!(due, expected);
+    }
+
+    #[test]
+    fn due_chunks_stop_at_the_end_of_the_digits() {
+        let card = |chunk| ChunkCard {
+            due: 0,
+            ..ChunkCard::new(chunk)
+        };
+        let scheduler = Scheduler::new([card(1), card(3)]);
+
+        // 25 digits make 3 chunks, the last one has 5 digits
+        assert_eq!(scheduler.due_chunks(0, 25), vec![1, 0, 2]);
+        assert_eq!(scheduler.due_chunks(0, 5), vec![0]);
+    }
+
+    #[test]
+    fn errors_lower_the_grade() {
+        let grades = (0..6).map(grade_from_errors).collect::<Vec<_>>();
+        assert_eq!(grades, vec![5, 4, 3, 2, 1, 1]);
+
+        let mut review = Review::new(VecDeque::from([4, 2]));
+        review.add_mistake();
+        review.add_mistake();
+        assert_eq!(
+            review.complete_chunk(),
+            Some(ChunkReview { chunk: 4, grade: 3 })
+        );
+        assert_eq!(
+            review.complete_chunk(),
+            Some(ChunkReview { chunk: 2, grade: 5 })
+        );
+        assert_eq!(review.complete_chunk(), None);
+    }
+}
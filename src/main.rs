@@ -1,6 +1,8 @@
 mod app;
 mod components;
 mod config;
+mod digits;
+mod game;
 
 use app::{preferences::AppPreferences, AppModel};
 use config::APP_ID;
@@ -19,7 +21,8 @@ fn main() {
     initialize_custom_icons();
     gtk::Window::set_default_icon_name("logo");
 
-    // launch app
+    // launch app with the preferences of the profile used last
+    app::storage::load_active_profile();
     app.run::<AppModel>(AppPreferences::load());
 }
 